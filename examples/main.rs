@@ -1,4 +1,5 @@
-use rayt_rs::scene_builder::SceneBuilder;
+use rayt_rs::{image::ppm, scene_builder::SceneBuilder};
+use std::io;
 
 fn main() -> anyhow::Result<()> {
     // Safety: just for logging
@@ -7,7 +8,12 @@ fn main() -> anyhow::Result<()> {
 
     let (camera, world) = SceneBuilder::build("scene1.toml")?;
 
-    camera.render(world);
+    let image = camera.render(&world);
+    // Write to the given path, or P3 to stdout.
+    match std::env::args().nth(1) {
+        Some(path) => image.save(path)?,
+        None => ppm::write_p3(&image, io::stdout().lock())?,
+    }
 
     Ok(())
 }
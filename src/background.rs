@@ -0,0 +1,196 @@
+//! What rays see when they leave the scene.
+
+use crate::math::types::{UnitVec3, Vec3};
+use crate::{color::Color, environment::EnvironmentMap, sky::PhysicalSky};
+use rand::Rng;
+use std::sync::Arc;
+
+/// The radiance arriving from outside the scene.
+///
+/// Configured by the TOML `[background]` table.
+#[derive(Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Background {
+    /// A white horizon fading to light blue overhead.
+    #[default]
+    Sky,
+    /// The same color in every direction.
+    Constant { color: Color },
+    /// Blends from `start` opposite `direction` to `end` along it.
+    Gradient {
+        start: Color,
+        end: Color,
+        #[serde(default = "Background::default_direction")]
+        direction: Vec3,
+    },
+    /// Daylight set by the position of the sun and the haze in the air.
+    Physical(Box<PhysicalSky>),
+    /// No light, so the scene is lit only by emitters.
+    #[serde(alias = "none")]
+    Black,
+    /// Light from an image, loaded by the scene builder from a `path`.
+    #[serde(deserialize_with = "Background::unloaded")]
+    Environment(Arc<EnvironmentMap>),
+}
+
+impl Background {
+    /// A constant background.
+    pub fn constant(color: Color) -> Self {
+        Background::Constant { color }
+    }
+
+    /// A gradient from `start` at the bottom to `end` at the top.
+    pub fn gradient(start: Color, end: Color) -> Self {
+        Background::Gradient {
+            start,
+            end,
+            direction: Background::default_direction(),
+        }
+    }
+
+    /// A physical daylight sky.
+    pub fn physical(sky: PhysicalSky) -> Self {
+        Background::Physical(Box::new(sky))
+    }
+
+    /// A background lit by an environment map.
+    pub fn environment(map: EnvironmentMap) -> Self {
+        Background::Environment(Arc::new(map))
+    }
+
+    /// Points a gradient along `direction`, leaving other backgrounds as they are.
+    pub fn direction(self, direction: impl Into<Vec3>) -> Self {
+        match self {
+            Background::Gradient { start, end, .. } => Background::Gradient {
+                start,
+                end,
+                direction: direction.into(),
+            },
+            other => other,
+        }
+    }
+
+    /// Returns the radiance seen along `direction`.
+    pub fn radiance(&self, direction: UnitVec3) -> Color {
+        match *self {
+            Background::Physical(ref sky) => sky.radiance(direction),
+            Background::Environment(ref map) => map.radiance(direction),
+            Background::Sky => {
+                Background::gradient(Color::WHITE, Color::new(0.5, 0.7, 1.0)).radiance(direction)
+            }
+            Background::Constant { color } => color,
+            Background::Gradient {
+                start,
+                end,
+                direction: axis,
+            } => {
+                let t = (direction.dot(axis.normalize_or(Vec3::Y)) + 1.0) / 2.0;
+                Color(start.lerp(*end, t))
+            }
+            Background::Black => Color::BLACK,
+        }
+    }
+
+    /// Samples a direction toward the light of the background with its
+    /// probability density over solid angle.
+    ///
+    /// Returns `None` for backgrounds that are only found by scattered rays.
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<(UnitVec3, f64)> {
+        match self {
+            Background::Physical(sky) => sky.sample(rng),
+            Background::Environment(map) => map.sample(rng),
+            _ => None,
+        }
+    }
+
+    /// Returns the density with which [`sample`](Background::sample) picks
+    /// `direction`, or `None` if the background is not sampled.
+    pub fn pdf(&self, direction: UnitVec3) -> Option<f64> {
+        match self {
+            Background::Physical(sky) => Some(sky.pdf(direction)),
+            Background::Environment(map) => Some(map.pdf(direction)),
+            _ => None,
+        }
+    }
+
+    /// Environment maps are read from files, which the scene builder
+    /// resolves relative to the scene.
+    fn unloaded<'de, D>(_: D) -> Result<Arc<EnvironmentMap>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Err(serde::de::Error::custom(
+            "environment maps are loaded by the scene builder",
+        ))
+    }
+
+    fn default_direction() -> Vec3 {
+        Vec3::Y
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        #[derive(Deserialize)]
+        struct Config {
+            background: Background,
+        }
+
+        let config: Config = toml::from_str("[background]\ntype = \"none\"").unwrap();
+        assert!(Background::Black == config.background);
+
+        let config: Config = toml::from_str("[background]\ntype = \"sky\"").unwrap();
+        assert!(Background::Sky == config.background);
+
+        let toml_str = r#"
+            [background]
+            type = "constant"
+            color = [0.1, 0.1, 0.1]
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Background::constant(Color::new(0.1, 0.1, 0.1)) == config.background);
+
+        let toml_str = r#"
+            [background]
+            type = "gradient"
+            start = [0.0, 0.0, 0.0]
+            end = [0.0, 0.0, 0.2]
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let expected = Background::gradient(Color::BLACK, Color::new(0.0, 0.0, 0.2));
+        assert!(expected == config.background);
+
+        let toml_str = r#"
+            [background]
+            type = "gradient"
+            start = [1.0, 1.0, 1.0]
+            end = [0.0, 0.0, 0.0]
+            direction = [1.0, 0.0, 0.0]
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let expected = Background::gradient(Color::WHITE, Color::BLACK).direction(Vec3::X);
+        assert!(expected == config.background);
+    }
+
+    #[test]
+    fn radiance() {
+        use super::*;
+
+        let sky = Background::Sky;
+        assert!(Color::new(0.5, 0.7, 1.0) == sky.radiance(Vec3::Y.into()));
+        assert!(Color::WHITE == sky.radiance(Vec3::NEG_Y.into()));
+        assert!(Color::BLACK == Background::Black.radiance(Vec3::Y.into()));
+
+        let gray = Color::new(0.5, 0.5, 0.5);
+        assert!(gray == Background::constant(gray).radiance(Vec3::Z.into()));
+
+        let gradient = Background::gradient(Color::BLACK, Color::WHITE).direction(Vec3::X);
+        assert!(Color::WHITE == gradient.radiance(Vec3::X.into()));
+        assert!(Color::BLACK == gradient.radiance(Vec3::NEG_X.into()));
+        assert!(gray == gradient.radiance(Vec3::Y.into()));
+    }
+}
@@ -0,0 +1,510 @@
+//! Bounding volume hierarchy.
+//!
+//! The hierarchy is built over object bounding boxes, either by splitting at
+//! the median centroid or by minimizing the binned surface area heuristic
+//! (SAH), and then flattened into a depth-first array of nodes.
+
+use crate::hittable::{DynHittable, HitRecord, Hittable};
+use crate::math::types::{Aabb, Interval, Point3};
+use crate::ray::Ray;
+use rayon::prelude::*;
+
+/// Trade-off between build time and traversal speed.
+#[derive(Copy, Clone, Default, PartialEq, Debug, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BvhQuality {
+    /// Median split along the longest axis.
+    Fast,
+    /// Binned SAH with few bins.
+    #[default]
+    Medium,
+    /// Binned SAH with many bins.
+    High,
+}
+
+impl BvhQuality {
+    /// Returns the number of SAH bins, or `None` for median splits.
+    fn bins(self) -> Option<usize> {
+        match self {
+            BvhQuality::Fast => None,
+            BvhQuality::Medium => Some(12),
+            BvhQuality::High => Some(32),
+        }
+    }
+}
+
+/// BVH build settings, read from the `[bvh]` TOML table.
+#[derive(Copy, Clone, PartialEq, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct BvhConfig {
+    quality: BvhQuality,
+    /// Largest number of objects in a leaf.
+    max_leaf_size: usize,
+}
+
+impl BvhConfig {
+    pub fn new() -> Self {
+        BvhConfig {
+            quality: BvhQuality::Medium,
+            max_leaf_size: 4,
+        }
+    }
+
+    pub fn quality(mut self, quality: BvhQuality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Sets the largest number of objects in a leaf.
+    pub fn max_leaf_size(mut self, size: usize) -> Self {
+        self.max_leaf_size = size.max(1);
+        self
+    }
+}
+
+impl Default for BvhConfig {
+    fn default() -> Self {
+        BvhConfig::new()
+    }
+}
+
+/// A node in the flattened hierarchy.
+///
+/// Interior nodes store their first child directly after themselves and the
+/// index of the second child in `offset`. Leaves store the range
+/// `offset..offset + count` of objects.
+#[derive(Clone, Copy)]
+struct LinearNode {
+    bbox: Aabb,
+    offset: usize,
+    count: usize,
+    axis: usize,
+}
+
+/// A bounding volume hierarchy stored as a flat array of nodes.
+pub struct Bvh {
+    nodes: Vec<LinearNode>,
+    objects: Vec<DynHittable>,
+    /// Objects without finite bounds, such as planes, tested before the tree.
+    unbounded: Vec<DynHittable>,
+}
+
+/// Deepest allowed tree, bounded by the traversal stack.
+const MAX_DEPTH: usize = 63;
+/// Smallest subtree built on a separate rayon task.
+const PARALLEL_THRESHOLD: usize = 4096;
+/// Cost of traversing a node relative to intersecting an object.
+const TRAVERSAL_COST: f64 = 0.125;
+
+/// An object's bounds during construction.
+#[derive(Clone, Copy)]
+struct BuildObject {
+    index: usize,
+    bbox: Aabb,
+    centroid: Point3,
+}
+
+enum BuildNode {
+    Leaf {
+        bbox: Aabb,
+        start: usize,
+        count: usize,
+    },
+    Interior {
+        bbox: Aabb,
+        axis: usize,
+        left: Box<BuildNode>,
+        right: Box<BuildNode>,
+    },
+}
+
+impl Bvh {
+    /// Builds a hierarchy over the given objects.
+    ///
+    /// Objects without finite bounds are kept out of the tree.
+    pub fn new(objects: &[DynHittable], config: BvhConfig) -> Self {
+        let (mut build, unbounded): (Vec<BuildObject>, Vec<BuildObject>) = objects
+            .par_iter()
+            .enumerate()
+            .map(|(index, o)| {
+                let bbox = o.bounding_box();
+                BuildObject {
+                    index,
+                    bbox,
+                    centroid: bbox.centroid(),
+                }
+            })
+            .partition(|b| b.bbox.is_finite());
+
+        let unbounded = unbounded.iter().map(|b| objects[b.index].clone()).collect();
+
+        if build.is_empty() {
+            return Bvh {
+                nodes: Vec::new(),
+                objects: Vec::new(),
+                unbounded,
+            };
+        }
+
+        let root = Bvh::build(&mut build, 0, 0, &config);
+
+        let mut nodes = Vec::with_capacity(2 * build.len());
+        Bvh::flatten(&root, &mut nodes);
+
+        let objects = build.iter().map(|b| objects[b.index].clone()).collect();
+
+        Bvh {
+            nodes,
+            objects,
+            unbounded,
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the hierarchy has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Recursively builds the subtree over `objects`, which start at `start`
+    /// in the final object order.
+    fn build(
+        objects: &mut [BuildObject],
+        start: usize,
+        depth: usize,
+        config: &BvhConfig,
+    ) -> BuildNode {
+        let bbox = objects
+            .iter()
+            .fold(Aabb::EMPTY, |bbox, o| bbox.union(o.bbox));
+        let count = objects.len();
+
+        let leaf = BuildNode::Leaf { bbox, start, count };
+        if count == 1 || depth >= MAX_DEPTH {
+            return leaf;
+        }
+
+        // Split along the longest axis of the centroids.
+        let (cmin, cmax) = objects.iter().fold(
+            (
+                Point3::splat(f64::INFINITY),
+                Point3::splat(f64::NEG_INFINITY),
+            ),
+            |(lo, hi), o| (lo.min(o.centroid), hi.max(o.centroid)),
+        );
+        let axis = (cmax - cmin).max_position();
+        let (lo, hi) = (cmin[axis], cmax[axis]);
+
+        // All centroids coincide, so no split separates them.
+        if hi - lo <= f64::EPSILON {
+            return leaf;
+        }
+
+        // Small nodes may become leaves when SAH finds no cheaper split.
+        let must_split = count > config.max_leaf_size;
+        let mid = match config.quality.bins() {
+            None if must_split => Bvh::median_split(objects, axis),
+            None => return leaf,
+            Some(bins) => match Bvh::sah_split(objects, bbox, axis, (lo, hi), bins, must_split) {
+                Some(mid) => mid,
+                None if must_split => Bvh::median_split(objects, axis),
+                None => return leaf,
+            },
+        };
+
+        let (l, r) = objects.split_at_mut(mid);
+        let (left, right) = if count >= PARALLEL_THRESHOLD {
+            rayon::join(
+                || Bvh::build(l, start, depth + 1, config),
+                || Bvh::build(r, start + mid, depth + 1, config),
+            )
+        } else {
+            (
+                Bvh::build(l, start, depth + 1, config),
+                Bvh::build(r, start + mid, depth + 1, config),
+            )
+        };
+
+        BuildNode::Interior {
+            bbox,
+            axis,
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    /// Partitions around the median centroid and returns the split index.
+    fn median_split(objects: &mut [BuildObject], axis: usize) -> usize {
+        let mid = objects.len() / 2;
+        objects.select_nth_unstable_by(mid, |a, b| a.centroid[axis].total_cmp(&b.centroid[axis]));
+        mid
+    }
+
+    /// Partitions at the cheapest bin boundary and returns the split index.
+    ///
+    /// Returns `None` if one side would be empty, or if a leaf is cheaper and
+    /// the split is optional.
+    fn sah_split(
+        objects: &mut [BuildObject],
+        bbox: Aabb,
+        axis: usize,
+        (lo, hi): (f64, f64),
+        bins: usize,
+        must_split: bool,
+    ) -> Option<usize> {
+        let bin_of = |o: &BuildObject| {
+            let b = ((o.centroid[axis] - lo) / (hi - lo) * bins as f64) as usize;
+            b.min(bins - 1)
+        };
+
+        let mut counts = vec![0usize; bins];
+        let mut bounds = vec![Aabb::EMPTY; bins];
+        for o in objects.iter() {
+            let b = bin_of(o);
+            counts[b] += 1;
+            bounds[b] = bounds[b].union(o.bbox);
+        }
+
+        // Sweep from the right to accumulate the cost of each right side.
+        let mut right_cost = vec![0.0; bins];
+        let (mut count, mut area) = (0, Aabb::EMPTY);
+        for b in (1..bins).rev() {
+            count += counts[b];
+            area = area.union(bounds[b]);
+            right_cost[b] = count as f64 * area.surface_area();
+        }
+
+        let (mut best, mut best_cost) = (0, f64::INFINITY);
+        let (mut count, mut area) = (0, Aabb::EMPTY);
+        for b in 0..bins - 1 {
+            count += counts[b];
+            area = area.union(bounds[b]);
+            let cost = count as f64 * area.surface_area() + right_cost[b + 1];
+            if cost < best_cost {
+                (best, best_cost) = (b, cost);
+            }
+        }
+
+        let split_cost = TRAVERSAL_COST + best_cost / bbox.surface_area();
+        if !must_split && split_cost >= objects.len() as f64 {
+            return None;
+        }
+
+        // Partition objects into bins `..=best` and `best + 1..`.
+        let mut mid = 0;
+        for i in 0..objects.len() {
+            if bin_of(&objects[i]) <= best {
+                objects.swap(i, mid);
+                mid += 1;
+            }
+        }
+
+        (0 < mid && mid < objects.len()).then_some(mid)
+    }
+
+    /// Appends the subtree in depth-first order and returns its root index.
+    fn flatten(node: &BuildNode, nodes: &mut Vec<LinearNode>) -> usize {
+        let index = nodes.len();
+        match node {
+            &BuildNode::Leaf { bbox, start, count } => nodes.push(LinearNode {
+                bbox,
+                offset: start,
+                count,
+                axis: 0,
+            }),
+            BuildNode::Interior {
+                bbox,
+                axis,
+                left,
+                right,
+            } => {
+                nodes.push(LinearNode {
+                    bbox: *bbox,
+                    offset: 0,
+                    count: 0,
+                    axis: *axis,
+                });
+                Bvh::flatten(left, nodes);
+                nodes[index].offset = Bvh::flatten(right, nodes);
+            }
+        }
+        index
+    }
+}
+
+impl Hittable for Bvh {
+    fn hit(&self, ray: &Ray, mut ray_t: Interval) -> Option<HitRecord> {
+        let mut record = None;
+        for o in &self.unbounded {
+            if let Some(r) = o.hit(ray, ray_t) {
+                ray_t.1 = r.t;
+                record = Some(r);
+            }
+        }
+
+        if self.nodes.is_empty() {
+            return record;
+        }
+
+        let direction = ray.direction();
+        let dir_is_neg = [direction.x < 0.0, direction.y < 0.0, direction.z < 0.0];
+
+        let mut stack = [0usize; MAX_DEPTH + 1];
+        let mut sp = 0;
+        let mut current = 0;
+
+        loop {
+            let node = &self.nodes[current];
+            if node.bbox.hit(ray, ray_t) {
+                if node.count > 0 {
+                    for o in &self.objects[node.offset..node.offset + node.count] {
+                        if let Some(r) = o.hit(ray, ray_t) {
+                            ray_t.1 = r.t;
+                            record = Some(r);
+                        }
+                    }
+                } else {
+                    // Visit the near child first.
+                    let (near, far) = if dir_is_neg[node.axis] {
+                        (node.offset, current + 1)
+                    } else {
+                        (current + 1, node.offset)
+                    };
+                    stack[sp] = far;
+                    sp += 1;
+                    current = near;
+                    continue;
+                }
+            }
+
+            if sp == 0 {
+                break;
+            }
+            sp -= 1;
+            current = stack[sp];
+        }
+
+        record
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let bbox = self.nodes.first().map_or(Aabb::EMPTY, |n| n.bbox);
+        self.unbounded
+            .iter()
+            .fold(bbox, |bbox, o| bbox.union(o.bounding_box()))
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [bvh]
+            quality = "high"
+            max_leaf_size = 2
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            bvh: BvhConfig,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(BvhConfig::new().quality(BvhQuality::High).max_leaf_size(2) == config.bvh);
+    }
+
+    #[test]
+    fn matches_linear_traversal() {
+        use super::*;
+        use crate::{
+            hittable::HittableList,
+            materials::MaterialId,
+            math::{primitives::Sphere, types::*},
+        };
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut list = HittableList::default();
+        for i in 0..500 {
+            let center = Point3::new(
+                rng.random_range(-10.0..10.0),
+                rng.random_range(-10.0..10.0),
+                rng.random_range(-10.0..10.0),
+            );
+            let radius = rng.random_range(0.1..1.0);
+            if i % 10 == 0 {
+                let center1 = center + Vec3::new(0.0, rng.random_range(0.0..2.0), 0.0);
+                list.add(Sphere::new_moving(center, center1, radius, MaterialId(i)));
+            } else {
+                list.add(Sphere::new(center, radius, MaterialId(i)));
+            }
+        }
+
+        let ray_t = Interval(0.001, f64::INFINITY);
+        let rays: Vec<Ray> = (0..2000)
+            .map(|_| {
+                let origin = Point3::new(
+                    rng.random_range(-15.0..15.0),
+                    rng.random_range(-15.0..15.0),
+                    rng.random_range(-15.0..15.0),
+                );
+                let direction = UnitVec3::random(&mut rng);
+                Ray::with_time(origin, direction, rng.random_range(0.0..1.0))
+            })
+            .collect();
+
+        for quality in [BvhQuality::Fast, BvhQuality::Medium, BvhQuality::High] {
+            for max_leaf_size in [1, 4] {
+                let config = BvhConfig::new()
+                    .quality(quality)
+                    .max_leaf_size(max_leaf_size);
+                let bvh = Bvh::new(list.objects(), config);
+                assert!(!bvh.is_empty());
+
+                for ray in &rays {
+                    match (list.hit(ray, ray_t), bvh.hit(ray, ray_t)) {
+                        (None, None) => {}
+                        (Some(a), Some(b)) => {
+                            assert!(a.t == b.t);
+                            assert!(a.material == b.material);
+                        }
+                        _ => panic!("bvh and linear traversal disagree"),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn coincident_objects() {
+        use super::*;
+        use crate::{
+            hittable::HittableList,
+            materials::MaterialId,
+            math::{primitives::Sphere, types::*},
+        };
+
+        let mut list = HittableList::default();
+        for i in 0..20 {
+            list.add(Sphere::new(Point3::new(0.0, 0.0, -2.0), 0.5, MaterialId(i)));
+        }
+
+        let bvh = Bvh::new(list.objects(), BvhConfig::new().max_leaf_size(1));
+        assert!(1 == bvh.len());
+
+        let ray = Ray::new(Point3::ZERO, Vec3::NEG_Z);
+        let record = bvh.hit(&ray, Interval(0.001, f64::INFINITY)).unwrap();
+        assert!((record.t - 1.5).abs() < 1e-12);
+
+        let empty = Bvh::new(&[], BvhConfig::new());
+        assert!(empty.is_empty());
+        assert!(empty.hit(&ray, Interval(0.001, f64::INFINITY)).is_none());
+    }
+}
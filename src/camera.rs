@@ -1,24 +1,52 @@
 //! This module defines the Camera, which parameterizes the world.
 
 use crate::math::{
-    constants::INFINITY,
-    types::{Point3, UnitVec3, Vec3},
+    constants::{INFINITY, PI},
+    types::{Point3, UnitVec3, Vec3, random_in_unit_disk},
 };
 use crate::{
-    color::Color, hittable::Hittable, ray::Ray, samplers::Sampler, samplers::SamplerConfig,
-    world::World,
+    color::Color, hittable::HitRecord, image::Image, materials::Material, ray::Ray,
+    samplers::Sampler, samplers::SamplerConfig, tonemap::DisplayTransform, world::World,
 };
-use indicatif::{ParallelProgressIterator, ProgressIterator, ProgressStyle};
+use indicatif::{ParallelProgressIterator, ProgressStyle};
+use rand::Rng;
 use rayon::prelude::*;
-use std::{
-    io::{self, Write},
-    sync::Arc,
-};
+
+/// How the camera maps pixels to rays.
+#[derive(Copy, Clone, Default, PartialEq, Debug, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Projection {
+    /// Rays diverge from the camera position through the viewport.
+    #[default]
+    Perspective,
+    /// Parallel rays along the view direction from points across the viewport.
+    Orthographic,
+    /// Full sphere of directions mapped by longitude and latitude onto a 2:1 image.
+    Equirectangular,
+    /// Circular fisheye inscribed in the image, see [`FisheyeMapping`].
+    Fisheye,
+    /// Six square faces along +X, -X, +Y, -Y, +Z, -Z laid out left to right.
+    ///
+    /// Faces are aligned to the world axes rather than the camera basis.
+    Cubemap,
+}
+
+/// How the angle from the view direction maps to the radius of a fisheye image.
+#[derive(Copy, Clone, Default, PartialEq, Debug, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FisheyeMapping {
+    /// Radius proportional to the angle.
+    #[default]
+    Equidistant,
+    /// Radius proportional to the sine of half the angle, preserving solid angle.
+    Equisolid,
+}
 
 /// The camera parameterizes and renders the [`World`].
 #[allow(unused)]
 pub struct Camera {
     sampler: Box<dyn Sampler + Sync>,
+    projection: Projection,
     image_width: usize,
     image_height: usize,
     position: Point3,
@@ -28,7 +56,15 @@ pub struct Camera {
     forward: UnitVec3,
     right: UnitVec3,
     up: UnitVec3,
+    defocus_angle: f64,
+    defocus_disk_u: Vec3,
+    defocus_disk_v: Vec3,
+    shutter_open: f64,
+    shutter_close: f64,
+    fisheye_mapping: FisheyeMapping,
+    fisheye_max_theta: f64,
     max_depth: i32,
+    display: DisplayTransform,
 }
 
 impl Camera {
@@ -36,9 +72,9 @@ impl Camera {
         CameraBuilder::default()
     }
 
-    pub fn render(self, world: World) {
-        let world = Arc::new(world);
-        let image_area = self.image_width * self.image_height;
+    /// Renders the world into an image of linear colors.
+    pub fn render(&self, world: &World) -> Image {
+        let mut image = Image::new(self.image_width, self.image_height).with_display(self.display);
 
         let ps = ProgressStyle::with_template(
             "[{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos:>7}/{len:7}",
@@ -46,80 +82,207 @@ impl Camera {
         .unwrap()
         .progress_chars("#>-");
 
+        // Build acceleration structures before starting the progress bar.
+        world.hittable();
+
         log::info!("Rendering Image");
 
-        let mut pixels: Vec<Color> = Vec::with_capacity(image_area);
-        pixels
-            .spare_capacity_mut() // MaybeUninit
+        image
+            .pixels_mut()
             .par_chunks_mut(self.image_width)
-            .progress_with_style(ps.clone())
+            .progress_with_style(ps)
             .enumerate()
             .for_each(|(j, row)| {
                 for (i, pixel) in row.iter_mut().enumerate().take(self.image_width) {
                     let mut color = Color::BLACK;
                     for sample in self.sampler.samples(i as f64, j as f64) {
-                        let ray = self.get_ray(sample);
-                        *color += *Camera::ray_color(&ray, &world, self.max_depth);
+                        if let Some(ray) = self.get_ray(sample) {
+                            *color += *Camera::ray_color(&ray, world, self.max_depth, None);
+                        }
                     }
 
                     let nsamples = self.sampler.nsamples() as f64;
-                    let color: Color = color.map(|e| e / nsamples).into();
-                    pixel.write(color);
+                    *pixel = color.map(|e| e / nsamples).into();
                 }
             });
 
-        // Safety: all elements are initialized.
-        unsafe { pixels.set_len(image_area) };
-
-        log::info!("Writing Image");
-
-        println!("P3\n{} {}\n255", self.image_width, self.image_height);
-
-        let mut out = io::BufWriter::new(io::stdout());
-        for &pixel_color in pixels.iter().progress_with_style(ps.clone()) {
-            writeln!(out, "{pixel_color}").expect("Failed write");
-        }
-        out.flush().unwrap();
+        image
     }
 
+    /// Returns the ray through an image sample, or `None` if the sample lies
+    /// outside the area covered by the projection.
     #[rustfmt::skip]
-    fn get_ray(&self, (sx, sy): (f64, f64)) -> Ray {
+    fn get_ray(&self, (sx, sy): (f64, f64)) -> Option<Ray> {
         let pixel_sample = self.pixel00_loc
             + (sx * self.pixel_delta_u)
             + (sy * self.pixel_delta_v);
 
-        let ray_direction =
-            UnitVec3::new_normalize(pixel_sample - self.position);
+        let (ray_origin, ray_direction) = match self.projection {
+            Projection::Perspective => {
+                let origin = if self.defocus_angle <= 0.0 {
+                    self.position
+                } else {
+                    self.defocus_disk_sample()
+                };
+                (origin, UnitVec3::new_normalize(pixel_sample - origin))
+            }
+            Projection::Orthographic => (pixel_sample, self.forward),
+            Projection::Equirectangular => {
+                let u = (sx + 0.5) / self.image_width as f64;
+                let v = (sy + 0.5) / self.image_height as f64;
+                (self.position, self.equirectangular_direction(u, v))
+            }
+            Projection::Fisheye => {
+                let radius = self.image_width.min(self.image_height) as f64 / 2.0;
+                let x = (sx + 0.5 - self.image_width as f64 / 2.0) / radius;
+                let y = (self.image_height as f64 / 2.0 - sy - 0.5) / radius;
+                (self.position, self.fisheye_direction(x, y)?)
+            }
+            Projection::Cubemap => {
+                let face_size = self.image_height as f64;
+                let face = ((sx + 0.5) / face_size).floor().clamp(0.0, 5.0);
+                let a = 2.0 * (sx + 0.5 - face * face_size) / face_size - 1.0;
+                let b = 1.0 - 2.0 * (sy + 0.5) / face_size;
+                (self.position, Camera::cubemap_direction(face as usize, a, b))
+            }
+        };
+
+        let ray_time = if self.shutter_open < self.shutter_close {
+            rand::rng().random_range(self.shutter_open..self.shutter_close)
+        } else {
+            self.shutter_open
+        };
+
+        Some(Ray::with_time(ray_origin, ray_direction, ray_time))
+    }
+
+    /// Maps normalized image coordinates to a direction on the unit sphere.
+    ///
+    /// The image center looks along `forward` and the top row along `up`.
+    fn equirectangular_direction(&self, u: f64, v: f64) -> UnitVec3 {
+        let longitude = (u - 0.5) * 2.0 * PI;
+        let latitude = (0.5 - v) * PI;
 
-        Ray::new(self.position, ray_direction)
+        let horizontal = longitude.sin() * *self.right + longitude.cos() * *self.forward;
+        UnitVec3::new_normalize(latitude.cos() * horizontal + latitude.sin() * *self.up)
     }
 
-    fn ray_color(ray: &Ray, world: &World, ttl: i32) -> Color {
+    /// Maps a point in the unit disk to a direction, or `None` outside the disk.
+    ///
+    /// The disk center looks along `forward`.
+    fn fisheye_direction(&self, x: f64, y: f64) -> Option<UnitVec3> {
+        let r = x.hypot(y);
+        if r > 1.0 {
+            return None;
+        }
+
+        let theta = match self.fisheye_mapping {
+            FisheyeMapping::Equidistant => r * self.fisheye_max_theta,
+            FisheyeMapping::Equisolid => 2.0 * (r * (self.fisheye_max_theta / 2.0).sin()).asin(),
+        };
+
+        let (cos_phi, sin_phi) = if r > 0.0 { (x / r, y / r) } else { (1.0, 0.0) };
+        let radial = cos_phi * *self.right + sin_phi * *self.up;
+        Some(UnitVec3::new_normalize(
+            theta.cos() * *self.forward + theta.sin() * radial,
+        ))
+    }
+
+    /// Maps a point `(a, b)` in `[-1, 1]` on a cube face to a direction.
+    ///
+    /// Each face is a 90 degree perspective view using the same
+    /// `right = forward x up` convention as the camera basis.
+    fn cubemap_direction(face: usize, a: f64, b: f64) -> UnitVec3 {
+        let (forward, up) = match face {
+            0 => (Vec3::X, Vec3::Y),
+            1 => (Vec3::NEG_X, Vec3::Y),
+            2 => (Vec3::Y, Vec3::Z),
+            3 => (Vec3::NEG_Y, Vec3::NEG_Z),
+            4 => (Vec3::Z, Vec3::Y),
+            _ => (Vec3::NEG_Z, Vec3::Y),
+        };
+        let right = forward.cross(up);
+        UnitVec3::new_normalize(forward + a * right + b * up)
+    }
+
+    /// Returns a random point on the camera's lens disk.
+    fn defocus_disk_sample(&self) -> Point3 {
+        let p = random_in_unit_disk(&mut rand::rng());
+        self.position + (p.x * self.defocus_disk_u) + (p.y * self.defocus_disk_v)
+    }
+
+    /// `scatter_pdf` is the density with which the previous bounce picked
+    /// `ray`, if that bounce also sampled the background directly.
+    fn ray_color(ray: &Ray, world: &World, ttl: i32, scatter_pdf: Option<f64>) -> Color {
         if ttl <= 0 {
             return Color::BLACK;
         }
 
-        if let Some(record) = world.objects().hit(ray, (0.001, INFINITY).into()) {
+        if let Some(record) = world.hittable().hit(ray, (0.001, INFINITY).into()) {
             let material = world.materials().get(record.material).expect("no material");
 
+            let emitted = material.emitted(&record);
+
             return if let Some((attenuation, scattered)) = material.scatter(ray, &record) {
-                attenuation * Camera::ray_color(&scattered, world, ttl - 1)
+                let pdf = material
+                    .evaluate(&record, scattered.direction())
+                    .map(|(_, pdf)| pdf);
+                emitted
+                    + Camera::sample_background(ray, &record, material.as_ref(), world)
+                    + attenuation * Camera::ray_color(&scattered, world, ttl - 1, pdf)
             } else {
-                material.emitted(&record)
+                emitted
             };
         }
 
-        let mut t = ray.direction().y;
-        t = (t + 1.0) / 2.0;
+        let radiance = world.background().radiance(ray.direction());
+        match (scatter_pdf, world.background().pdf(ray.direction())) {
+            (Some(scatter_pdf), Some(light_pdf)) => {
+                radiance * power_heuristic(scatter_pdf, light_pdf)
+            }
+            _ => radiance,
+        }
+    }
 
-        // background gradient
-        let start = Color(Vec3::new(1.0, 1.0, 1.0));
-        let end = Color(Vec3::new(0.5, 0.7, 1.0));
+    /// Traces a shadow ray toward a sampled direction of the background,
+    /// weighted against finding it by scattering.
+    fn sample_background(
+        ray: &Ray,
+        record: &HitRecord,
+        material: &dyn Material,
+        world: &World,
+    ) -> Color {
+        let background = world.background();
+        let Some((direction, light_pdf)) = background.sample(&mut rand::rng()) else {
+            return Color::BLACK;
+        };
+        let Some((f, scatter_pdf)) = material.evaluate(record, direction) else {
+            return Color::BLACK;
+        };
+        if f == Color::BLACK {
+            return Color::BLACK;
+        }
 
-        Color((1.0 - t) * start.0 + t * end.0)
+        let shadow = Ray::with_time(record.hit_point, *direction, ray.time());
+        if world
+            .hittable()
+            .hit(&shadow, (0.001, INFINITY).into())
+            .is_some()
+        {
+            return Color::BLACK;
+        }
+
+        background.radiance(direction) * f * (power_heuristic(light_pdf, scatter_pdf) / light_pdf)
     }
 }
 
+/// Weights a sample drawn with density `a` against another strategy with
+/// density `b` for multiple importance sampling.
+fn power_heuristic(a: f64, b: f64) -> f64 {
+    let (a2, b2) = (a * a, b * b);
+    if a2 + b2 > 0.0 { a2 / (a2 + b2) } else { 0.0 }
+}
+
 impl Default for Camera {
     fn default() -> Camera {
         CameraBuilder::default().build()
@@ -127,7 +290,10 @@ impl Default for Camera {
 }
 
 /// Created through [`Camera::builder`].
+///
+/// Fields missing from the TOML `[camera]` table take their default values.
 #[derive(serde::Deserialize)]
+#[serde(default)]
 pub struct CameraBuilder {
     sampler: SamplerConfig,
     aspect_ratio: f64,
@@ -135,7 +301,30 @@ pub struct CameraBuilder {
     position: Point3,
     look_at: Vec3,
     up: Vec3,
+    /// Projection used to generate rays.
+    projection: Projection,
+    /// Vertical field of view in degrees.
+    vfov: f64,
+    /// Height of the viewport in world units for orthographic projection.
+    ortho_height: f64,
+    /// Field of view across the image circle in degrees for fisheye projection.
+    fisheye_fov: f64,
+    /// Lens mapping for fisheye projection.
+    fisheye_mapping: FisheyeMapping,
+    /// Variation angle of rays through each pixel in degrees.
+    defocus_angle: f64,
+    /// Distance from the camera to the plane of perfect focus.
+    ///
+    /// Defaults to the distance between `position` and `look_at`.
+    focus_distance: Option<f64>,
+    /// Time at which the shutter opens.
+    shutter_open: f64,
+    /// Time at which the shutter closes.
+    shutter_close: f64,
     max_depth: i32,
+    /// Display transform attached to rendered images, set from `[output]`.
+    #[serde(skip)]
+    display: DisplayTransform,
 }
 
 impl CameraBuilder {
@@ -147,33 +336,61 @@ impl CameraBuilder {
         let up = UnitVec3::new_normalize(right.cross(*forward));
 
         // Image Dimensions.
-        let image_width = self.image_width;
-        let mut image_height: usize = (image_width as f64 / self.aspect_ratio) as usize;
-        image_height = if image_height < 1 { 1 } else { image_height };
+        let (image_width, image_height) = match self.projection {
+            Projection::Cubemap => {
+                let face_size = (self.image_width / 6).max(1);
+                (6 * face_size, face_size)
+            }
+            _ => {
+                let aspect_ratio = match self.projection {
+                    Projection::Equirectangular => 2.0,
+                    _ => self.aspect_ratio,
+                };
+                let image_height = (self.image_width as f64 / aspect_ratio) as usize;
+                (self.image_width, image_height.max(1))
+            }
+        };
 
         // Viewport Dimensions.
-        let focal_length = 1.0;
-        let viewport_height = 2.0;
+        let focus_distance = self
+            .focus_distance
+            .unwrap_or_else(|| (self.look_at - position).length());
+        let viewport_height = match self.projection {
+            Projection::Orthographic => self.ortho_height,
+            _ => {
+                let h = (self.vfov.to_radians() / 2.0).tan();
+                2.0 * h * focus_distance
+            }
+        };
         let viewport_width = viewport_height * image_width as f64 / image_height as f64;
 
         // Viewport uv
-        let viewport_u = Vec3::new(viewport_width, 0.0, 0.0);
-        let viewport_v = Vec3::new(0.0, -viewport_height, 0.0);
+        let viewport_u = viewport_width * *right;
+        let viewport_v = viewport_height * -*up;
 
         // Pixel delta uv
         let pixel_delta_u = viewport_u / image_width as f64;
         let pixel_delta_v = viewport_v / image_height as f64;
 
         // Position of upper left pixel.
-        let viewport_upper_left =
-            position - Vec3::new(viewport_width / 2.0, -viewport_height / 2.0, focal_length);
+        let viewport_center = match self.projection {
+            Projection::Orthographic => position,
+            _ => position + focus_distance * *forward,
+        };
+        let viewport_upper_left = viewport_center - viewport_u / 2.0 - viewport_v / 2.0;
         let pixel00_loc = viewport_upper_left + 0.5 * (pixel_delta_u + pixel_delta_v);
 
+        // Defocus disk basis.
+        let defocus_radius = focus_distance * (self.defocus_angle.to_radians() / 2.0).tan();
+        let defocus_disk_u = defocus_radius * *right;
+        let defocus_disk_v = defocus_radius * *up;
+
         // Sampler.
         let sampler = self.sampler.into_sampler();
 
         Camera {
             sampler,
+            projection: self.projection,
             image_width,
             image_height,
             position,
@@ -183,7 +400,15 @@ impl CameraBuilder {
             forward,
             right,
             up,
+            defocus_angle: self.defocus_angle,
+            defocus_disk_u,
+            defocus_disk_v,
+            shutter_open: self.shutter_open,
+            shutter_close: self.shutter_close,
+            fisheye_mapping: self.fisheye_mapping,
+            fisheye_max_theta: self.fisheye_fov.clamp(0.0, 360.0).to_radians() / 2.0,
             max_depth: self.max_depth,
+            display: self.display,
         }
     }
 
@@ -217,10 +442,70 @@ impl CameraBuilder {
         self
     }
 
+    /// Sets the projection used to generate rays.
+    pub fn projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    /// Sets the viewport height in world units for orthographic projection.
+    pub fn ortho_height(mut self, height: impl Into<f64>) -> Self {
+        self.ortho_height = height.into();
+        self
+    }
+
+    /// Sets the field of view across the image circle in degrees, up to 360.
+    pub fn fisheye_fov(mut self, fov: impl Into<f64>) -> Self {
+        self.fisheye_fov = fov.into();
+        self
+    }
+
+    /// Sets the lens mapping for fisheye projection.
+    pub fn fisheye_mapping(mut self, mapping: FisheyeMapping) -> Self {
+        self.fisheye_mapping = mapping;
+        self
+    }
+
+    /// Sets the vertical field of view in degrees.
+    pub fn vfov(mut self, vfov: impl Into<f64>) -> Self {
+        self.vfov = vfov.into();
+        self
+    }
+
+    /// Sets the lens aperture as a cone angle in degrees. Zero disables depth of field.
+    pub fn defocus_angle(mut self, angle: impl Into<f64>) -> Self {
+        self.defocus_angle = angle.into();
+        self
+    }
+
+    /// Sets the distance from the camera to the plane of perfect focus.
+    pub fn focus_distance(mut self, distance: impl Into<f64>) -> Self {
+        self.focus_distance = Some(distance.into());
+        self
+    }
+
+    /// Sets the time at which the shutter opens.
+    pub fn shutter_open(mut self, time: impl Into<f64>) -> Self {
+        self.shutter_open = time.into();
+        self
+    }
+
+    /// Sets the time at which the shutter closes.
+    pub fn shutter_close(mut self, time: impl Into<f64>) -> Self {
+        self.shutter_close = time.into();
+        self
+    }
+
     pub fn max_depth(mut self, depth: impl Into<i32>) -> Self {
         self.max_depth = depth.into();
         self
     }
+
+    /// Sets the display transform attached to rendered images.
+    pub fn display(mut self, display: DisplayTransform) -> Self {
+        self.display = display;
+        self
+    }
 }
 
 impl Default for CameraBuilder {
@@ -232,7 +517,17 @@ impl Default for CameraBuilder {
             position: Point3::splat(0.0),
             look_at: Vec3::NEG_Z,
             up: Vec3::Y,
+            projection: Projection::Perspective,
+            vfov: 90.0,
+            ortho_height: 2.0,
+            fisheye_fov: 180.0,
+            fisheye_mapping: FisheyeMapping::Equidistant,
+            defocus_angle: 0.0,
+            focus_distance: None,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
             max_depth: 10,
+            display: DisplayTransform::default(),
         }
     }
 }
@@ -251,6 +546,11 @@ mod tests {
             position = [1.0, 1.0, 1.0]
             look_at = [0.0, 0.0, -2.0]
             up = [0.0, 2.0, 0.0]
+            vfov = 45.0
+            defocus_angle = 0.6
+            focus_distance = 3.4
+            shutter_open = 0.25
+            shutter_close = 0.75
             max_depth = 25
 
             [camera.sampler]
@@ -268,6 +568,302 @@ mod tests {
         assert!(Point3::splat(1.0) == config.camera.position);
         assert!(Vec3::new(0.0, 0.0, -2.0) == config.camera.look_at);
         assert!(Vec3::new(0.0, 2.0, 0.0) == config.camera.up);
+        assert!(45.0 == config.camera.vfov);
+        assert!(0.6 == config.camera.defocus_angle);
+        assert!(Some(3.4) == config.camera.focus_distance);
+        assert!(0.25 == config.camera.shutter_open);
+        assert!(0.75 == config.camera.shutter_close);
         assert!(25 == config.camera.max_depth);
     }
+
+    #[test]
+    fn deserialize_defaults() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [camera]
+            image_width = 200
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            camera: CameraBuilder,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(200 == config.camera.image_width);
+        assert!(90.0 == config.camera.vfov);
+        assert!(SamplerConfig::Single == config.camera.sampler);
+    }
+
+    #[test]
+    fn center_ray_follows_look_at() {
+        use super::*;
+
+        let camera = Camera::builder()
+            .image_width(3usize)
+            .position(Point3::new(1.0, 2.0, 3.0))
+            .look_at(Point3::new(4.0, 2.0, 3.0))
+            .build();
+
+        let ray = camera.get_ray((1.0, 1.0)).unwrap();
+        assert!(Point3::new(1.0, 2.0, 3.0) == ray.origin());
+        assert!(ray.direction().abs_diff_eq(Vec3::X, 1e-12));
+    }
+
+    #[test]
+    fn corner_rays_follow_basis() {
+        use super::*;
+
+        // Looking down +X with +Y up puts +Z on the right.
+        let camera = Camera::builder()
+            .image_width(2usize)
+            .look_at(Vec3::X)
+            .up(Vec3::Y)
+            .vfov(90.0)
+            .build();
+
+        let upper_left = camera.get_ray((-0.5, -0.5)).unwrap().direction();
+        let lower_right = camera.get_ray((1.5, 1.5)).unwrap().direction();
+        assert!(upper_left.abs_diff_eq(Vec3::new(1.0, 1.0, -1.0).normalize(), 1e-12));
+        assert!(lower_right.abs_diff_eq(Vec3::new(1.0, -1.0, 1.0).normalize(), 1e-12));
+    }
+
+    #[test]
+    fn vfov_scales_viewport() {
+        use super::*;
+
+        // Looking straight up with -Z as the top of the image.
+        let camera = Camera::builder()
+            .image_width(2usize)
+            .look_at(Vec3::Y)
+            .up(Vec3::NEG_Z)
+            .vfov(60.0)
+            .build();
+
+        let top = camera.get_ray((0.5, -0.5)).unwrap().direction();
+        let expected = Vec3::new(0.0, 1.0, -(30.0f64).to_radians().tan()).normalize();
+        assert!(top.abs_diff_eq(expected, 1e-12));
+    }
+
+    #[test]
+    fn defocus_rays_converge_on_focus_plane() {
+        use super::*;
+
+        let camera = Camera::builder()
+            .image_width(3usize)
+            .look_at(Vec3::NEG_Z)
+            .defocus_angle(10.0)
+            .focus_distance(4.0)
+            .build();
+
+        let radius = 4.0 * (5.0f64).to_radians().tan();
+        let focus_point = Point3::new(0.0, 0.0, -4.0);
+
+        for _ in 0..100 {
+            let ray = camera.get_ray((1.0, 1.0)).unwrap();
+            assert!(ray.origin().z == 0.0);
+            assert!(ray.origin().length() <= radius);
+
+            let t = 4.0 / ray.direction().dot(Vec3::NEG_Z);
+            assert!(ray.at(t).abs_diff_eq(focus_point, 1e-9));
+        }
+    }
+
+    #[test]
+    fn ray_times_within_shutter() {
+        use super::*;
+
+        let camera = Camera::builder()
+            .shutter_open(0.25)
+            .shutter_close(0.75)
+            .build();
+
+        for _ in 0..100 {
+            let time = camera.get_ray((0.0, 0.0)).unwrap().time();
+            assert!((0.25..0.75).contains(&time));
+        }
+
+        let camera = Camera::builder().build();
+        assert!(0.0 == camera.get_ray((0.0, 0.0)).unwrap().time());
+    }
+
+    #[test]
+    fn deserialize_orthographic() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [camera]
+            projection = "orthographic"
+            ortho_height = 4.0
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            camera: CameraBuilder,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Projection::Orthographic == config.camera.projection);
+        assert!(4.0 == config.camera.ortho_height);
+    }
+
+    #[test]
+    fn orthographic_rays_are_parallel() {
+        use super::*;
+
+        let camera = Camera::builder()
+            .image_width(2usize)
+            .position(Point3::new(0.0, 0.0, 5.0))
+            .look_at(Point3::ZERO)
+            .projection(Projection::Orthographic)
+            .ortho_height(4.0)
+            .build();
+
+        let upper_left = camera.get_ray((-0.5, -0.5)).unwrap();
+        let lower_right = camera.get_ray((1.5, 1.5)).unwrap();
+        assert!(upper_left.direction().abs_diff_eq(Vec3::NEG_Z, 1e-12));
+        assert!(lower_right.direction().abs_diff_eq(Vec3::NEG_Z, 1e-12));
+        assert!(
+            upper_left
+                .origin()
+                .abs_diff_eq(Point3::new(-2.0, 2.0, 5.0), 1e-12)
+        );
+        assert!(
+            lower_right
+                .origin()
+                .abs_diff_eq(Point3::new(2.0, -2.0, 5.0), 1e-12)
+        );
+    }
+
+    #[test]
+    fn equirectangular_covers_sphere() {
+        use super::*;
+
+        let camera = Camera::builder()
+            .image_width(4usize)
+            .aspect_ratio(1.0)
+            .look_at(Vec3::NEG_Z)
+            .projection(Projection::Equirectangular)
+            .build();
+
+        // Always 2:1 regardless of aspect ratio.
+        assert!(2 == camera.image_height);
+
+        let center = camera.get_ray((1.5, 0.5)).unwrap().direction();
+        let right = camera.get_ray((2.5, 0.5)).unwrap().direction();
+        let behind = camera.get_ray((-0.5, 0.5)).unwrap().direction();
+        let top = camera.get_ray((1.5, -0.5)).unwrap().direction();
+        assert!(center.abs_diff_eq(Vec3::NEG_Z, 1e-12));
+        assert!(right.abs_diff_eq(Vec3::X, 1e-12));
+        assert!(behind.abs_diff_eq(Vec3::Z, 1e-12));
+        assert!(top.abs_diff_eq(Vec3::Y, 1e-12));
+    }
+
+    #[test]
+    fn deserialize_fisheye() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [camera]
+            projection = "fisheye"
+            fisheye_fov = 360.0
+            fisheye_mapping = "equisolid"
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            camera: CameraBuilder,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Projection::Fisheye == config.camera.projection);
+        assert!(360.0 == config.camera.fisheye_fov);
+        assert!(FisheyeMapping::Equisolid == config.camera.fisheye_mapping);
+    }
+
+    #[test]
+    fn fisheye_maps_radius_to_angle() {
+        use super::*;
+
+        for mapping in [FisheyeMapping::Equidistant, FisheyeMapping::Equisolid] {
+            let camera = Camera::builder()
+                .image_width(4usize)
+                .look_at(Vec3::NEG_Z)
+                .projection(Projection::Fisheye)
+                .fisheye_fov(360.0)
+                .fisheye_mapping(mapping)
+                .build();
+
+            let center = camera.get_ray((1.5, 1.5)).unwrap().direction();
+            let edge_right = camera.get_ray((3.5, 1.5)).unwrap().direction();
+            let edge_top = camera.get_ray((1.5, -0.5)).unwrap().direction();
+            assert!(center.abs_diff_eq(Vec3::NEG_Z, 1e-12));
+            assert!(edge_right.abs_diff_eq(Vec3::Z, 1e-7));
+            assert!(edge_top.abs_diff_eq(Vec3::Z, 1e-7));
+
+            // Corners lie outside the image circle.
+            assert!(camera.get_ray((-0.5, -0.5)).is_none());
+        }
+
+        let camera = Camera::builder()
+            .image_width(4usize)
+            .look_at(Vec3::NEG_Z)
+            .projection(Projection::Fisheye)
+            .fisheye_fov(180.0)
+            .build();
+
+        let halfway = camera.get_ray((2.5, 1.5)).unwrap().direction();
+        let expected = Vec3::new(1.0, 0.0, -1.0).normalize();
+        assert!(halfway.abs_diff_eq(expected, 1e-12));
+    }
+
+    #[test]
+    fn cubemap_faces_follow_axes() {
+        use super::*;
+
+        let camera = Camera::builder()
+            .image_width(13usize)
+            .projection(Projection::Cubemap)
+            .build();
+
+        assert!(12 == camera.image_width);
+        assert!(2 == camera.image_height);
+
+        let faces = [
+            Vec3::X,
+            Vec3::NEG_X,
+            Vec3::Y,
+            Vec3::NEG_Y,
+            Vec3::Z,
+            Vec3::NEG_Z,
+        ];
+        for (face, axis) in faces.into_iter().enumerate() {
+            let center = (2 * face) as f64 + 0.5;
+            let direction = camera.get_ray((center, 0.5)).unwrap().direction();
+            assert!(direction.abs_diff_eq(axis, 1e-12));
+        }
+    }
+
+    #[test]
+    fn render_image() {
+        use super::*;
+
+        let camera = Camera::builder()
+            .image_width(8usize)
+            .aspect_ratio(2.0)
+            .build();
+        let image = camera.render(&World::default());
+
+        assert!(8 == image.width());
+        assert!(4 == image.height());
+
+        // The sky gradient is brighter in blue toward the top.
+        let top = image.get(0, 0).unwrap();
+        let bottom = image.get(0, 3).unwrap();
+        assert!(top.x < bottom.x);
+    }
 }
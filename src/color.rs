@@ -1,9 +1,9 @@
 //! Color type, operations, and trait impls.
 
-use crate::math::types::{Interval, Vec3};
+use crate::{math::types::Vec3, tonemap::DisplayTransform};
 use std::{
     fmt,
-    ops::{Deref, DerefMut, Mul},
+    ops::{Add, Deref, DerefMut, Mul},
 };
 
 #[derive(Copy, Clone, PartialEq, serde::Deserialize)]
@@ -13,16 +13,13 @@ impl Color {
     pub const BLACK: Color = Color(Vec3::splat(0.0));
     pub const WHITE: Color = Color(Vec3::splat(1.0));
 
-    const GAMMA: f64 = 1.0 / 2.2;
-    const INTENSITY: Interval = Interval(0.0, 0.999);
-
     pub fn new(r: f64, g: f64, b: f64) -> Self {
         Color(Vec3::new(r, g, b))
     }
 
-    /// Converts linear to gamma.
-    fn linear_to_gamma(self) -> Self {
-        Color(self.powf(Color::GAMMA))
+    /// Returns the relative luminance of linear Rec. 709 primaries.
+    pub fn luminance(&self) -> f64 {
+        self.0.dot(Vec3::new(0.2126, 0.7152, 0.0722))
     }
 }
 
@@ -46,6 +43,14 @@ impl From<Vec3> for Color {
     }
 }
 
+impl Add for Color {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Color(self.0 + rhs.0)
+    }
+}
+
 impl Mul<f64> for Color {
     type Output = Self;
 
@@ -64,11 +69,7 @@ impl Mul for Color {
 
 impl fmt::Display for Color {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let c = self.linear_to_gamma();
-
-        let r = (256.0 * Color::INTENSITY.clamp(c.0.x)) as u8;
-        let g = (256.0 * Color::INTENSITY.clamp(c.0.y)) as u8;
-        let b = (256.0 * Color::INTENSITY.clamp(c.0.z)) as u8;
+        let [r, g, b] = DisplayTransform::default().to_rgb8(*self);
 
         write!(f, "{r} {g} {b}")
     }
@@ -0,0 +1,258 @@
+//! Image based lighting from an equirectangular environment map.
+
+use crate::math::{
+    constants::PI,
+    types::{UnitVec3, Vec3},
+};
+use crate::{color::Color, image::Image};
+use glam::DQuat;
+use rand::Rng;
+use std::{io, path::Path};
+
+/// Radiance arriving from every direction, read from a latitude-longitude
+/// image.
+///
+/// The center of the image lies along -Z with +Y at the top row, matching
+/// the equirectangular [`Projection`](crate::camera::Projection), so a map
+/// rendered by the camera lights a scene the way it was captured.
+///
+/// Directions are sampled in proportion to the luminance of each pixel, so
+/// a small bright sun is found by shadow rays instead of by chance.
+#[derive(Clone, PartialEq)]
+pub struct EnvironmentMap {
+    image: Image,
+    /// Turns the map about the y axis.
+    rotation: DQuat,
+    /// Scales the radiance of every pixel.
+    intensity: f64,
+    /// The probability of picking each pixel, in row-major order.
+    pixel_pdf: Vec<f64>,
+    /// The cumulative probability of picking each row or any above it.
+    marginal: Vec<f64>,
+    /// The cumulative probability of each column within its row.
+    conditional: Vec<f64>,
+}
+
+impl EnvironmentMap {
+    pub fn new(image: Image) -> Self {
+        let (width, height) = (image.width(), image.height());
+
+        // Rows near the poles cover less solid angle than rows at the horizon.
+        let weights: Vec<f64> = image
+            .rows()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                let sin_theta = ((y as f64 + 0.5) / height as f64 * PI).sin();
+                row.iter().map(move |c| c.luminance().max(0.0) * sin_theta)
+            })
+            .collect();
+        let total: f64 = weights.iter().sum();
+
+        let mut pixel_pdf = vec![0.0; weights.len()];
+        let mut marginal = vec![0.0; height];
+        let mut conditional = vec![0.0; weights.len()];
+        if total > 0.0 {
+            let mut row_cdf = 0.0;
+            for y in 0..height {
+                let row = &weights[y * width..(y + 1) * width];
+                let row_total: f64 = row.iter().sum();
+                row_cdf += row_total / total;
+                marginal[y] = row_cdf;
+
+                let mut cdf = 0.0;
+                for (x, w) in row.iter().enumerate() {
+                    pixel_pdf[y * width + x] = w / total;
+                    cdf += w / row_total.max(f64::MIN_POSITIVE);
+                    conditional[y * width + x] = cdf;
+                }
+            }
+        }
+
+        EnvironmentMap {
+            image,
+            rotation: DQuat::IDENTITY,
+            intensity: 1.0,
+            pixel_pdf,
+            marginal,
+            conditional,
+        }
+    }
+
+    /// Loads a Radiance `.hdr` or `.pfm` image.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Image::open(path).map(EnvironmentMap::new)
+    }
+
+    /// Turns the map by degrees about the y axis.
+    pub fn rotation(mut self, degrees: f64) -> Self {
+        self.rotation = DQuat::from_rotation_y(degrees.to_radians());
+        self
+    }
+
+    /// Scales the radiance of every pixel.
+    pub fn intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    /// Returns the radiance arriving along `direction`.
+    pub fn radiance(&self, direction: UnitVec3) -> Color {
+        self.pixel(direction)
+            .map_or(Color::BLACK, |(x, y, _)| self.texel(x, y) * self.intensity)
+    }
+
+    /// Samples a direction in proportion to the luminance of the map,
+    /// returning it with its probability density over solid angle.
+    ///
+    /// Returns `None` if the map is black.
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<(UnitVec3, f64)> {
+        let (width, height) = (self.image.width(), self.image.height());
+        if self.marginal.last().is_none_or(|&total| total <= 0.0) {
+            return None;
+        }
+
+        let (xi_row, xi_column) = (rng.random::<f64>(), rng.random::<f64>());
+        let y = self
+            .marginal
+            .partition_point(|&c| c <= xi_row * self.marginal[height - 1])
+            .min(height - 1);
+        let column_cdf = &self.conditional[y * width..(y + 1) * width];
+        let x = column_cdf
+            .partition_point(|&c| c <= xi_column * column_cdf[width - 1])
+            .min(width - 1);
+
+        let u = (x as f64 + rng.random::<f64>()) / width as f64;
+        let v = (y as f64 + rng.random::<f64>()) / height as f64;
+        let direction = self.direction(u, v);
+
+        let pdf = self.pdf(direction);
+        (pdf > 0.0).then_some((direction, pdf))
+    }
+
+    /// Returns the probability density over solid angle with which
+    /// [`sample`](EnvironmentMap::sample) picks `direction`.
+    pub fn pdf(&self, direction: UnitVec3) -> f64 {
+        let Some((x, y, sin_theta)) = self.pixel(direction) else {
+            return 0.0;
+        };
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+
+        // Pixels are picked uniformly in image space, which maps to the
+        // sphere with a Jacobian of 2 pi^2 sin(theta).
+        let texels = (self.image.width() * self.image.height()) as f64;
+        self.pixel_pdf[y * self.image.width() + x] * texels / (2.0 * PI * PI * sin_theta)
+    }
+
+    /// Returns the pixel seen along `direction` and the sine of its polar angle.
+    fn pixel(&self, direction: UnitVec3) -> Option<(usize, usize, f64)> {
+        let (width, height) = (self.image.width(), self.image.height());
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let d = self.rotation.inverse() * *direction;
+        let u = 0.5 + d.x.atan2(-d.z) / (2.0 * PI);
+        let theta = d.y.clamp(-1.0, 1.0).acos();
+        let v = theta / PI;
+
+        let x = ((u * width as f64) as usize).min(width - 1);
+        let y = ((v * height as f64) as usize).min(height - 1);
+        Some((x, y, theta.sin()))
+    }
+
+    /// Returns the direction through image coordinates in `[0, 1]`.
+    fn direction(&self, u: f64, v: f64) -> UnitVec3 {
+        let longitude = (u - 0.5) * 2.0 * PI;
+        let theta = v * PI;
+        let local = Vec3::new(
+            theta.sin() * longitude.sin(),
+            theta.cos(),
+            -theta.sin() * longitude.cos(),
+        );
+        UnitVec3::new_normalize(self.rotation * local)
+    }
+
+    fn texel(&self, x: usize, y: usize) -> Color {
+        self.image.pixels()[y * self.image.width() + x]
+    }
+}
+
+mod tests {
+    #[test]
+    fn lookup() {
+        use super::*;
+
+        // Columns are centered on -X+Z, -X-Z, +X-Z and +X+Z from left to right.
+        let colors = [
+            Color::new(1.0, 0.0, 0.0),
+            Color::new(0.0, 1.0, 0.0),
+            Color::new(0.0, 0.0, 1.0),
+            Color::WHITE,
+        ];
+        let pixels = [colors, colors].concat();
+        let map = EnvironmentMap::new(Image::from_pixels(4, 2, pixels));
+
+        let dir = |x: f64, z: f64| UnitVec3::new_normalize(Vec3::new(x, 0.1, z));
+        assert!(colors[0] == map.radiance(dir(-1.0, 1.0)));
+        assert!(colors[1] == map.radiance(dir(-1.0, -1.0)));
+        assert!(colors[2] == map.radiance(dir(1.0, -1.0)));
+        assert!(colors[3] == map.radiance(dir(1.0, 1.0)));
+
+        // Turned a quarter turn counterclockwise seen from above.
+        let map = map.rotation(90.0).intensity(2.0);
+        assert!(colors[2] * 2.0 == map.radiance(dir(-1.0, -1.0)));
+    }
+
+    #[test]
+    fn importance_sampling() {
+        use super::*;
+        use rand::{SeedableRng, rngs::StdRng};
+
+        // A dim map with one bright pixel just above the horizon.
+        let (width, height) = (16, 8);
+        let mut pixels = vec![Color::new(0.01, 0.01, 0.01); width * height];
+        pixels[3 * width + 5] = Color::new(1000.0, 1000.0, 1000.0);
+        let map = EnvironmentMap::new(Image::from_pixels(width, height, pixels)).rotation(30.0);
+        let sun = map.radiance(map.direction(5.5 / 16.0, 3.5 / 8.0));
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples: Vec<_> = (0..1000).map(|_| map.sample(&mut rng).unwrap()).collect();
+        let bright = samples
+            .iter()
+            .filter(|(d, _)| map.radiance(*d) == sun)
+            .count();
+        assert!(bright > 990);
+
+        // The returned density agrees with the density of the direction.
+        for (direction, pdf) in &samples {
+            assert!((pdf - map.pdf(*direction)).abs() < 1e-9 * pdf);
+        }
+
+        // The density integrates to one over the sphere.
+        let n = 200;
+        let mut integral = 0.0;
+        for i in 0..n {
+            for j in 0..2 * n {
+                let theta = (i as f64 + 0.5) / n as f64 * PI;
+                let phi = (j as f64 + 0.5) / (2 * n) as f64 * 2.0 * PI;
+                let d = Vec3::new(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                );
+                let solid_angle = theta.sin() * (PI / n as f64) * (PI / n as f64);
+                integral += map.pdf(UnitVec3::new_normalize(d)) * solid_angle;
+            }
+        }
+        assert!((integral - 1.0).abs() < 0.05);
+
+        let black = EnvironmentMap::new(Image::new(4, 2));
+        assert!(black.sample(&mut rng).is_none());
+    }
+}
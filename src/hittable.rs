@@ -1,7 +1,8 @@
 //! This module defines a trait for hittable objects.
 
-use crate::math::types::{Interval, Point3, UnitVec3};
+use crate::math::types::{Aabb, Interval, Point3, UnitVec3};
 use crate::{materials::MaterialId, ray::Ray};
+use std::sync::Arc;
 
 #[derive(Default)]
 pub enum FrontFace {
@@ -21,6 +22,9 @@ pub struct HitRecord {
     pub front_face: FrontFace,
     /// The material of the surface.
     pub material: MaterialId,
+    /// The surface coordinates of the intersection point.
+    pub u: f64,
+    pub v: f64,
 }
 
 impl HitRecord {
@@ -44,24 +48,62 @@ impl HitRecord {
             normal,
             front_face,
             material,
+            u: 0.0,
+            v: 0.0,
         }
     }
+
+    /// Sets the surface coordinates.
+    pub fn with_uv(mut self, u: f64, v: f64) -> Self {
+        self.u = u;
+        self.v = v;
+        self
+    }
 }
 
 /// Allows a type to be tested for ray intersections.
 pub trait Hittable {
     fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord>;
+
+    /// Returns a box enclosing the object over all ray times.
+    fn bounding_box(&self) -> Aabb;
+}
+
+pub type DynHittable = Arc<dyn Hittable + Send + Sync>;
+
+impl<H: Hittable + ?Sized> Hittable for Arc<H> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        (**self).hit(ray, ray_t)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
 }
 
 #[derive(Default)]
-pub struct HittableList(Vec<Box<dyn Hittable + Send + Sync>>);
+pub struct HittableList {
+    objects: Vec<DynHittable>,
+    bbox: Aabb,
+}
 
 impl HittableList {
     pub fn add<T>(&mut self, o: T)
     where
         T: Hittable + Send + Sync + 'static,
     {
-        self.0.push(Box::new(o))
+        self.bbox = self.bbox.union(o.bounding_box());
+        self.objects.push(Arc::new(o))
+    }
+
+    /// Returns the objects in the list.
+    pub fn objects(&self) -> &[DynHittable] {
+        &self.objects
+    }
+
+    /// Returns `true` if the list has no objects.
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
     }
 }
 
@@ -69,7 +111,7 @@ impl Hittable for HittableList {
     /// Iterate through all hittable objects to find the closest hit.
     fn hit(&self, ray: &Ray, mut ray_t: Interval) -> Option<HitRecord> {
         let mut record = None;
-        for o in &self.0 {
+        for o in &self.objects {
             record = o.hit(ray, ray_t).map_or(record, |r| {
                 ray_t.1 = r.t;
                 Some(r)
@@ -78,4 +120,8 @@ impl Hittable for HittableList {
 
         record
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
 }
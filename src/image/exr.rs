@@ -0,0 +1,164 @@
+//! OpenEXR writer.
+
+use crate::image::Image;
+use exr::prelude::{
+    AnyChannel, AnyChannels, Encoding, FlatSamples, ImageAttributes, IntegerBounds, Layer,
+    LayerAttributes, SmallVec, WritableImage, f16,
+};
+use std::io::{self, Cursor, Write};
+
+/// Bits per channel of an EXR image.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub enum ExrPrecision {
+    /// 16-bit half floats.
+    #[default]
+    Half,
+    /// 32-bit floats.
+    Float,
+}
+
+/// Options for writing EXR images.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct ExrOptions {
+    /// Bits per channel.
+    pub precision: ExrPrecision,
+    /// Whether to add an opaque alpha channel.
+    pub alpha: bool,
+}
+
+/// Writes a single layer EXR.
+///
+/// Pixels are written as scene-linear radiance without clamping.
+pub fn write_exr(image: &Image, out: impl Write, options: ExrOptions) -> io::Result<()> {
+    write_exr_layers(&[("", image)], out, options)
+}
+
+/// Writes a multi-layer EXR, one layer per named image.
+///
+/// All images must have the same dimensions.
+pub fn write_exr_layers(
+    layers: &[(&str, &Image)],
+    mut out: impl Write,
+    options: ExrOptions,
+) -> io::Result<()> {
+    let Some((_, first)) = layers.first() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "no layers"));
+    };
+    let size = (first.width(), first.height());
+
+    if layers
+        .iter()
+        .any(|(_, image)| (image.width(), image.height()) != size)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "layer dimensions differ",
+        ));
+    }
+
+    let layers: Vec<_> = layers
+        .iter()
+        .map(|&(name, image)| {
+            let attributes = if name.is_empty() {
+                LayerAttributes::default()
+            } else {
+                LayerAttributes::named(name)
+            };
+            Layer::new(
+                size,
+                attributes,
+                Encoding::FAST_LOSSLESS,
+                channels(image, options),
+            )
+        })
+        .collect();
+
+    let attributes = ImageAttributes::new(IntegerBounds::from_dimensions(size));
+    let exr_image = exr::prelude::Image::from_layers(attributes, layers);
+
+    // The encoder needs to seek, so encode in memory first.
+    let mut buf = Cursor::new(Vec::new());
+    exr_image
+        .write()
+        .to_buffered(&mut buf)
+        .map_err(|err| match err {
+            exr::error::Error::Io(err) => err,
+            err => io::Error::other(err),
+        })?;
+
+    out.write_all(buf.get_ref())?;
+    out.flush()
+}
+
+/// Splits an image into named sample channels.
+fn channels(image: &Image, options: ExrOptions) -> AnyChannels<FlatSamples> {
+    let samples = |f: fn(&crate::color::Color) -> f64| -> FlatSamples {
+        let values = image.pixels().iter().map(f);
+        match options.precision {
+            ExrPrecision::Half => FlatSamples::F16(values.map(f16::from_f64).collect()),
+            ExrPrecision::Float => FlatSamples::F32(values.map(|e| e as f32).collect()),
+        }
+    };
+
+    let mut list: SmallVec<[AnyChannel<FlatSamples>; 4]> = SmallVec::new();
+    list.push(AnyChannel::new("R", samples(|c| c.x)));
+    list.push(AnyChannel::new("G", samples(|c| c.y)));
+    list.push(AnyChannel::new("B", samples(|c| c.z)));
+    if options.alpha {
+        list.push(AnyChannel::new("A", samples(|_| 1.0)));
+    }
+
+    AnyChannels::sort(list)
+}
+
+mod tests {
+    #[test]
+    fn exr_roundtrip() {
+        use super::*;
+        use crate::color::Color;
+        use exr::prelude::{ReadChannels, ReadLayers};
+
+        let image = Image::from_pixels(2, 1, vec![Color::new(0.5, 2.0, 8.0), Color::BLACK]);
+        let options = ExrOptions {
+            precision: ExrPrecision::Float,
+            alpha: true,
+        };
+
+        let mut out = Vec::new();
+        write_exr_layers(&[("beauty", &image), ("copy", &image)], &mut out, options).unwrap();
+
+        let read = exr::prelude::read()
+            .no_deep_data()
+            .largest_resolution_level()
+            .all_channels()
+            .all_layers()
+            .all_attributes()
+            .from_buffered(std::io::Cursor::new(out))
+            .unwrap();
+
+        assert!(2 == read.layer_data.len());
+
+        let layer = &read.layer_data[0];
+        assert!(
+            Some("beauty")
+                == layer
+                    .attributes
+                    .layer_name
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .as_deref()
+        );
+
+        let names: Vec<String> = layer
+            .channel_data
+            .list
+            .iter()
+            .map(|c| c.name.to_string())
+            .collect();
+        assert!(vec!["A", "B", "G", "R"] == names);
+
+        let blue = &layer.channel_data.list[1].sample_data;
+        assert!(8.0 == blue.value_by_flat_index(0).to_f32());
+        assert!(0.0 == blue.value_by_flat_index(1).to_f32());
+    }
+}
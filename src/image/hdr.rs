@@ -0,0 +1,258 @@
+//! Radiance RGBE reader and writer.
+
+use crate::{color::Color, image::Image};
+use std::io::{self, BufRead, Write};
+
+/// Reads a Radiance `.hdr` image with flat or run-length encoded scanlines.
+///
+/// Only the standard `-Y height +X width` orientation is supported.
+pub fn read_hdr(mut input: impl BufRead) -> io::Result<Image> {
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    if !line.starts_with("#?") {
+        return Err(invalid("missing Radiance signature"));
+    }
+
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Err(invalid("missing resolution"));
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix("FORMAT=")
+            && format != "32-bit_rle_rgbe"
+        {
+            return Err(invalid(&format!("unsupported format {format}")));
+        }
+    }
+
+    line.clear();
+    input.read_line(&mut line)?;
+    let (height, width): (usize, usize) = match line.split_whitespace().collect::<Vec<_>>()[..] {
+        ["-Y", h, "+X", w] => h.parse().ok().zip(w.parse().ok()),
+        _ => None,
+    }
+    .ok_or_else(|| invalid(&format!("unsupported resolution: {}", line.trim())))?;
+    if width == 0 || height == 0 {
+        return Err(invalid(&format!("empty image: {}", line.trim())));
+    }
+
+    width
+        .checked_mul(height)
+        .ok_or_else(|| invalid(&format!("image too large: {}", line.trim())))?;
+
+    // Buffers grow as data arrives, so a bad header cannot force a huge
+    // allocation.
+    let mut pixels = Vec::new();
+    let mut scanline = Vec::new();
+    for _ in 0..height {
+        read_scanline(&mut input, width, &mut scanline)?;
+        pixels.extend(scanline.iter().map(|&rgbe| from_rgbe(rgbe)));
+    }
+
+    Ok(Image::from_pixels(width, height, pixels))
+}
+
+/// Reads one scanline of `width` pixels, which is flat unless it starts with
+/// the `2 2` marker of the run-length encoding.
+fn read_scanline(
+    input: &mut impl BufRead,
+    width: usize,
+    scanline: &mut Vec<[u8; 4]>,
+) -> io::Result<()> {
+    let mut first = [0u8; 4];
+    input.read_exact(&mut first)?;
+    scanline.clear();
+
+    if !(8..0x8000).contains(&width) || first[0] != 2 || first[1] != 2 || first[2] & 0x80 != 0 {
+        scanline.push(first);
+        for _ in 1..width {
+            let mut pixel = [0u8; 4];
+            input.read_exact(&mut pixel)?;
+            scanline.push(pixel);
+        }
+        return Ok(());
+    }
+
+    if usize::from(first[2]) << 8 | usize::from(first[3]) != width {
+        return Err(invalid("scanline width mismatch"));
+    }
+    // Run-length encoded widths are below 2^15.
+    scanline.resize(width, [0; 4]);
+
+    // Each channel is stored separately as runs and literal spans.
+    for channel in 0..4 {
+        let mut x = 0;
+        while x < width {
+            let mut count = [0u8; 1];
+            input.read_exact(&mut count)?;
+            let (run, count) = match count[0] {
+                c if c > 128 => (true, usize::from(c - 128)),
+                c => (false, usize::from(c)),
+            };
+            if count == 0 || x + count > width {
+                return Err(invalid("bad scanline run"));
+            }
+
+            let mut byte = [0u8; 1];
+            if run {
+                input.read_exact(&mut byte)?;
+            }
+            for pixel in &mut scanline[x..x + count] {
+                if !run {
+                    input.read_exact(&mut byte)?;
+                }
+                pixel[channel] = byte[0];
+            }
+            x += count;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes an uncompressed Radiance `.hdr` image.
+///
+/// Pixels are written as scene-linear radiance without clamping.
+pub fn write_hdr(image: &Image, out: impl Write) -> io::Result<()> {
+    let mut out = io::BufWriter::new(out);
+
+    write!(out, "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n")?;
+    writeln!(out, "-Y {} +X {}", image.height(), image.width())?;
+    for pixel_color in image.pixels() {
+        out.write_all(&to_rgbe(*pixel_color))?;
+    }
+
+    out.flush()
+}
+
+/// The largest value RGBE can hold, a full mantissa at the top exponent:
+/// 255 / 256 * 2^127.
+const RGBE_MAX: f64 = 255.0 / 256.0 * 1.7014118346046923e38;
+
+/// Encodes a color as RGB mantissas sharing one exponent.
+///
+/// NaN encodes as zero and values beyond the range of RGBE are clamped.
+fn to_rgbe(c: Color) -> [u8; 4] {
+    let c = c.0.to_array().map(|x| {
+        if x.is_nan() {
+            0.0
+        } else {
+            x.clamp(0.0, RGBE_MAX)
+        }
+    });
+    let v = c[0].max(c[1]).max(c[2]);
+    if v < 1e-32 {
+        return [0; 4];
+    }
+
+    // v = m * 2^e with m in [0.5, 1), correcting the rounding of log2.
+    let mut e = v.log2().floor() as i32 + 1;
+    if v >= 2f64.powi(e) {
+        e += 1;
+    } else if v < 2f64.powi(e - 1) {
+        e -= 1;
+    }
+    let scale = 256.0 / 2f64.powi(e);
+
+    let [r, g, b] = c.map(|x| (x * scale).min(255.0) as u8);
+    [r, g, b, (e + 128) as u8]
+}
+
+/// Decodes RGB mantissas sharing one exponent.
+fn from_rgbe([r, g, b, e]: [u8; 4]) -> Color {
+    if e == 0 {
+        return Color::BLACK;
+    }
+
+    let scale = 2f64.powi(i32::from(e) - 136);
+    Color::new(f64::from(r), f64::from(g), f64::from(b)) * scale
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+mod tests {
+    #[test]
+    fn hdr() {
+        use super::*;
+
+        let image = Image::from_pixels(2, 1, vec![Color::new(1.0, 0.5, 0.25), Color::BLACK]);
+
+        let mut out = Vec::new();
+        write_hdr(&image, &mut out).unwrap();
+
+        let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 2\n";
+        assert!(header == &out[..header.len()]);
+        assert!([128, 64, 32, 129, 0, 0, 0, 0] == out[header.len()..]);
+    }
+
+    #[test]
+    fn rgbe_unclamped() {
+        use super::*;
+
+        assert!([128, 0, 0, 136] == to_rgbe(Color::new(128.0, 0.0, 0.0)));
+        assert!([0, 0, 0, 0] == to_rgbe(Color::BLACK));
+
+        // Out of range and invalid values.
+        assert!([255, 0, 0, 255] == to_rgbe(Color::new(f64::INFINITY, 0.0, f64::NAN)));
+        assert!([255, 255, 0, 255] == to_rgbe(Color::new(1e300, 2f64.powi(127), -1.0)));
+        assert!([0, 0, 0, 0] == to_rgbe(Color::new(f64::NAN, f64::NAN, f64::NAN)));
+
+        // Just below and at a power of two.
+        let below = 1.0 - f64::EPSILON / 2.0;
+        assert!([255, 0, 0, 128] == to_rgbe(Color::new(below, 0.0, 0.0)));
+        assert!([128, 0, 0, 129] == to_rgbe(Color::new(1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn read_round_trip() {
+        use super::*;
+
+        let pixels = vec![
+            Color::new(1.0, 0.5, 0.25),
+            Color::BLACK,
+            Color::new(64.0, 0.0, 2.0),
+        ];
+        let image = Image::from_pixels(3, 1, pixels.clone());
+
+        let mut out = Vec::new();
+        write_hdr(&image, &mut out).unwrap();
+        let read = read_hdr(&out[..]).unwrap();
+
+        assert!(3 == read.width() && 1 == read.height());
+        assert!(pixels == read.pixels());
+    }
+
+    #[test]
+    fn read_run_length() {
+        use super::*;
+
+        let mut data = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\nEXPOSURE=1.0\n\n-Y 1 +X 8\n".to_vec();
+        data.extend([2, 2, 0, 8]);
+        // Red and green as runs, blue as literals, then a shared exponent.
+        data.extend([136, 128, 136, 64]);
+        data.extend([8, 0, 0, 0, 0, 128, 128, 128, 128]);
+        data.extend([136, 129]);
+
+        let image = read_hdr(&data[..]).unwrap();
+        assert!(8 == image.width());
+        assert!(Some(Color::new(1.0, 0.5, 0.0)) == image.get(0, 0));
+        assert!(Some(Color::new(1.0, 0.5, 1.0)) == image.get(7, 0));
+
+        assert!(read_hdr(&b"P6\n"[..]).is_err());
+        let empty = b"#?RADIANCE\n\n-Y 1 +X 0\n";
+        assert!(read_hdr(&empty[..]).err().unwrap().kind() == io::ErrorKind::InvalidData);
+
+        let huge = b"#?RADIANCE\n\n-Y 4000000000 +X 4000000000\n";
+        let kind = read_hdr(&huge[..]).err().unwrap().kind();
+        assert!(io::ErrorKind::InvalidData == kind || io::ErrorKind::UnexpectedEof == kind);
+        let truncated = b"#?RADIANCE\n\n-Y 100000 +X 100000\n\x01\x02\x03\x04";
+        let kind = read_hdr(&truncated[..]).err().unwrap().kind();
+        assert!(io::ErrorKind::UnexpectedEof == kind);
+    }
+}
@@ -0,0 +1,230 @@
+//! Rendered images, readers and writers.
+
+use crate::{color::Color, tonemap::DisplayTransform};
+use std::{
+    fs::File,
+    io::{self, BufReader, Write},
+    path::Path,
+};
+
+pub mod exr;
+pub mod hdr;
+pub mod pfm;
+pub mod png;
+pub mod ppm;
+
+use self::{exr::ExrOptions, png::PngDepth};
+
+/// Supported output file formats.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ImageFormat {
+    /// Binary (`P6`) portable pixmap.
+    Ppm,
+    /// RGB portable network graphics.
+    Png(PngDepth),
+    /// Linear 32-bit float portable float map.
+    Pfm,
+    /// Linear OpenEXR.
+    Exr(ExrOptions),
+    /// Linear Radiance RGBE.
+    Hdr,
+}
+
+impl ImageFormat {
+    /// Selects a format from a file extension, case insensitive.
+    ///
+    /// `.png` selects 8 bits per channel and `.exr` selects half float RGB.
+    pub fn from_extension(ext: &str) -> Option<ImageFormat> {
+        match ext.to_ascii_lowercase().as_str() {
+            "ppm" => Some(ImageFormat::Ppm),
+            "png" => Some(ImageFormat::Png(PngDepth::Eight)),
+            "pfm" => Some(ImageFormat::Pfm),
+            "exr" => Some(ImageFormat::Exr(ExrOptions::default())),
+            "hdr" => Some(ImageFormat::Hdr),
+            _ => None,
+        }
+    }
+
+    /// Selects a format from the extension of a path.
+    ///
+    /// A `.16.png` suffix selects 16 bits per channel.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<ImageFormat> {
+        let path = path.as_ref();
+        let format = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(ImageFormat::from_extension)?;
+
+        let depth = Path::new(path.file_stem()?).extension();
+        match format {
+            ImageFormat::Png(_) if depth.is_some_and(|d| d == "16") => {
+                Some(ImageFormat::Png(PngDepth::Sixteen))
+            }
+            _ => Some(format),
+        }
+    }
+
+    /// Writes an image in this format.
+    pub fn write(self, image: &Image, out: impl Write) -> io::Result<()> {
+        match self {
+            ImageFormat::Ppm => ppm::write_p6(image, out),
+            ImageFormat::Png(depth) => png::write_png(image, out, depth),
+            ImageFormat::Pfm => pfm::write_pfm(image, out),
+            ImageFormat::Exr(options) => exr::write_exr(image, out, options),
+            ImageFormat::Hdr => hdr::write_hdr(image, out),
+        }
+    }
+}
+
+/// An owned buffer of linear [`Color`] pixels in row-major order, starting
+/// at the upper left corner.
+///
+/// The display transform is used by writers of display-referred formats.
+/// Linear formats ignore it.
+#[derive(Clone, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+    display: DisplayTransform,
+}
+
+impl Image {
+    /// Constructs an image filled with [`Color::BLACK`].
+    pub fn new(width: usize, height: usize) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![Color::BLACK; width * height],
+            display: DisplayTransform::default(),
+        }
+    }
+
+    /// Constructs an image from row-major pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pixels.len() != width * height`.
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert_eq!(pixels.len(), width * height, "pixel count mismatch");
+        Image {
+            width,
+            height,
+            pixels,
+            display: DisplayTransform::default(),
+        }
+    }
+
+    /// Sets the display transform.
+    pub fn with_display(mut self, display: DisplayTransform) -> Self {
+        self.display = display;
+        self
+    }
+
+    /// Returns the display transform.
+    pub fn display(&self) -> &DisplayTransform {
+        &self.display
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the pixels in row-major order.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// Returns a mutable slice of the pixels in row-major order.
+    pub fn pixels_mut(&mut self) -> &mut [Color] {
+        &mut self.pixels
+    }
+
+    /// Returns the pixel at column `x` and row `y`.
+    pub fn get(&self, x: usize, y: usize) -> Option<Color> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    /// Returns an iterator over the rows of the image.
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[Color]> {
+        self.pixels.chunks(self.width.max(1))
+    }
+
+    /// Opens a linear `.hdr` or `.pfm` image selected by its extension.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Image> {
+        let path = path.as_ref();
+        let input = || File::open(path).map(BufReader::new);
+        match ImageFormat::from_path(path) {
+            Some(ImageFormat::Hdr) => hdr::read_hdr(input()?),
+            Some(ImageFormat::Pfm) => pfm::read_pfm(input()?),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unsupported image format: {}", path.display()),
+            )),
+        }
+    }
+
+    /// Saves the image to a file in the format selected by its extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let format = ImageFormat::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unsupported image format: {}", path.display()),
+            )
+        })?;
+
+        self.save_with_format(path, format)
+    }
+
+    /// Saves the image to a file in the given format.
+    pub fn save_with_format(&self, path: impl AsRef<Path>, format: ImageFormat) -> io::Result<()> {
+        format.write(self, File::create(path)?)
+    }
+}
+
+mod tests {
+    #[test]
+    fn get_pixel() {
+        use super::*;
+
+        let pixels = vec![
+            Color::new(0.0, 0.0, 0.0),
+            Color::new(1.0, 0.0, 0.0),
+            Color::new(0.0, 1.0, 0.0),
+            Color::new(0.0, 0.0, 1.0),
+            Color::new(1.0, 1.0, 0.0),
+            Color::new(1.0, 1.0, 1.0),
+        ];
+        let image = Image::from_pixels(3, 2, pixels);
+
+        assert!(3 == image.width());
+        assert!(2 == image.height());
+        assert!(Some(Color::new(0.0, 1.0, 0.0)) == image.get(2, 0));
+        assert!(Some(Color::new(0.0, 0.0, 1.0)) == image.get(0, 1));
+        assert!(image.get(3, 0).is_none());
+        assert!(2 == image.rows().count());
+    }
+
+    #[test]
+    fn format_from_path() {
+        use super::*;
+
+        assert!(Some(ImageFormat::Ppm) == ImageFormat::from_path("out/img.ppm"));
+        assert!(Some(ImageFormat::Png(PngDepth::Eight)) == ImageFormat::from_path("img.PNG"));
+        assert!(Some(ImageFormat::Png(PngDepth::Sixteen)) == ImageFormat::from_path("img.16.png"));
+        assert!(Some(ImageFormat::Png(PngDepth::Eight)) == ImageFormat::from_path("img.0016.png"));
+        assert!(Some(ImageFormat::Hdr) == ImageFormat::from_path("img.16.hdr"));
+        assert!(Some(ImageFormat::Pfm) == ImageFormat::from_path("img.pfm"));
+        assert!(Some(ImageFormat::Hdr) == ImageFormat::from_path("img.hdr"));
+        assert!(Some(ImageFormat::Exr(ExrOptions::default())) == ImageFormat::from_path("img.exr"));
+        assert!(ImageFormat::from_path("img.jpg").is_none());
+        assert!(ImageFormat::from_path("img").is_none());
+    }
+}
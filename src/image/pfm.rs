@@ -0,0 +1,174 @@
+//! Portable float map reader and writer.
+
+use crate::{color::Color, image::Image};
+use std::io::{self, BufRead, Read, Write};
+
+/// Reads an RGB (`PF`) or grayscale (`Pf`) portable float map.
+///
+/// The sign of the scale selects the byte order and its magnitude is ignored.
+pub fn read_pfm(mut input: impl BufRead) -> io::Result<Image> {
+    let mut header = Vec::new();
+    // Magic, dimensions and scale, each ending in one whitespace character.
+    while header.len() < 4 {
+        let token = read_token(&mut input)?;
+        if token.is_empty() {
+            return Err(invalid("truncated header"));
+        }
+        header.push(token);
+    }
+
+    let channels = match header[0].as_str() {
+        "PF" => 3,
+        "Pf" => 1,
+        magic => return Err(invalid(&format!("unsupported magic {magic}"))),
+    };
+    let parse = |s: &str| s.parse::<usize>().map_err(|_| invalid("bad dimensions"));
+    let (width, height) = (parse(&header[1])?, parse(&header[2])?);
+    let little_endian = header[3]
+        .parse::<f64>()
+        .map_err(|_| invalid("bad scale"))?
+        .is_sign_negative();
+
+    let row_len = width
+        .checked_mul(channels * 4)
+        .filter(|_| width.checked_mul(height).is_some())
+        .ok_or_else(|| invalid("image too large"))?;
+
+    // Buffers grow as data arrives, so a bad header cannot force a huge
+    // allocation.
+    let mut row = Vec::new();
+    let mut rows = Vec::new();
+    for _ in 0..height {
+        row.clear();
+        input.by_ref().take(row_len as u64).read_to_end(&mut row)?;
+        if row.len() < row_len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "truncated pixel data",
+            ));
+        }
+        let floats: Vec<f64> = row
+            .chunks_exact(4)
+            .map(|b| {
+                let b = b.try_into().unwrap();
+                f64::from(match little_endian {
+                    true => f32::from_le_bytes(b),
+                    false => f32::from_be_bytes(b),
+                })
+            })
+            .collect();
+        rows.push(
+            floats
+                .chunks_exact(channels)
+                .map(|c| match *c {
+                    [r, g, b] => Color::new(r, g, b),
+                    [y] => Color::new(y, y, y),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    // Rows are stored bottom to top.
+    let pixels = rows.into_iter().rev().flatten().collect();
+    Ok(Image::from_pixels(width, height, pixels))
+}
+
+/// Reads a whitespace delimited token and the single character after it.
+fn read_token(input: &mut impl BufRead) -> io::Result<String> {
+    let mut token = String::new();
+    let mut byte = [0u8; 1];
+    while input.read(&mut byte)? == 1 {
+        match byte[0] {
+            b if b.is_ascii_whitespace() && token.is_empty() => continue,
+            b if b.is_ascii_whitespace() => break,
+            b => token.push(char::from(b)),
+        }
+    }
+    Ok(token)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Writes a little-endian RGB portable float map.
+///
+/// Pixels are written as linear 32-bit floats without clamping. Rows are
+/// stored bottom to top as the format requires.
+pub fn write_pfm(image: &Image, out: impl Write) -> io::Result<()> {
+    let mut out = io::BufWriter::new(out);
+
+    // A negative scale marks little-endian data.
+    write!(out, "PF\n{} {}\n-1.0\n", image.width(), image.height())?;
+    for row in image.rows().rev() {
+        for pixel_color in row {
+            for e in pixel_color.to_array() {
+                out.write_all(&(e as f32).to_le_bytes())?;
+            }
+        }
+    }
+
+    out.flush()
+}
+
+mod tests {
+    #[test]
+    fn pfm() {
+        use super::*;
+        use crate::color::Color;
+
+        let top = Color::new(2.0, 0.5, 0.0);
+        let bottom = Color::new(0.25, 4.0, 1.0);
+        let image = Image::from_pixels(1, 2, vec![top, bottom]);
+
+        let mut out = Vec::new();
+        write_pfm(&image, &mut out).unwrap();
+
+        let header = b"PF\n1 2\n-1.0\n";
+        assert!(header == &out[..header.len()]);
+
+        let floats: Vec<f32> = out[header.len()..]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        assert!(vec![0.25, 4.0, 1.0, 2.0, 0.5, 0.0] == floats);
+    }
+
+    #[test]
+    fn read_round_trip() {
+        use super::*;
+
+        let pixels = vec![Color::new(2.0, 0.5, 0.0), Color::new(0.25, 4.0, 1.0)];
+        let image = Image::from_pixels(1, 2, pixels.clone());
+
+        let mut out = Vec::new();
+        write_pfm(&image, &mut out).unwrap();
+        let read = read_pfm(&out[..]).unwrap();
+
+        assert!(1 == read.width() && 2 == read.height());
+        assert!(pixels == read.pixels());
+    }
+
+    #[test]
+    fn read_big_endian_gray() {
+        use super::*;
+
+        let mut data = b"Pf\n2 1\n1.0\n".to_vec();
+        data.extend(0.5f32.to_be_bytes());
+        data.extend(3.0f32.to_be_bytes());
+
+        let image = read_pfm(&data[..]).unwrap();
+        assert!(Some(Color::new(0.5, 0.5, 0.5)) == image.get(0, 0));
+        assert!(Some(Color::new(3.0, 3.0, 3.0)) == image.get(1, 0));
+
+        assert!(read_pfm(&b"P6\n1 1\n255\n"[..]).is_err());
+
+        let huge = b"PF\n1 100000000000000\n-1.0\n";
+        let kind = read_pfm(&huge[..]).err().unwrap().kind();
+        assert!(io::ErrorKind::UnexpectedEof == kind);
+        let overflow = b"PF\n4000000000000000000 4\n-1.0\n";
+        let kind = read_pfm(&overflow[..]).err().unwrap().kind();
+        assert!(io::ErrorKind::InvalidData == kind);
+    }
+}
@@ -0,0 +1,75 @@
+//! Portable network graphics writer.
+
+use crate::image::Image;
+use std::io::{self, Write};
+
+/// Bits per channel of a PNG image.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub enum PngDepth {
+    #[default]
+    Eight,
+    Sixteen,
+}
+
+/// Writes an RGB PNG.
+///
+/// Pixels are converted with the image's display transform and quantized to
+/// the given bit depth.
+pub fn write_png(image: &Image, out: impl Write, depth: PngDepth) -> io::Result<()> {
+    let mut encoder = png::Encoder::new(out, image.width() as u32, image.height() as u32);
+    encoder.set_color(png::ColorType::Rgb);
+
+    let data: Vec<u8> = match depth {
+        PngDepth::Eight => {
+            encoder.set_depth(png::BitDepth::Eight);
+            let display = image.display();
+            image
+                .pixels()
+                .iter()
+                .flat_map(|&c| display.to_rgb8(c))
+                .collect()
+        }
+        PngDepth::Sixteen => {
+            encoder.set_depth(png::BitDepth::Sixteen);
+            image
+                .pixels()
+                .iter()
+                .flat_map(|&c| image.display().to_rgb16(c))
+                .flat_map(u16::to_be_bytes)
+                .collect()
+        }
+    };
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+    writer.finish()?;
+
+    Ok(())
+}
+
+mod tests {
+    #[test]
+    fn png_roundtrip() {
+        use super::*;
+        use crate::color::Color;
+
+        let image = Image::from_pixels(2, 1, vec![Color::BLACK, Color::WHITE]);
+
+        for (depth, expected) in [
+            (PngDepth::Eight, vec![0, 0, 0, 255, 255, 255]),
+            (PngDepth::Sixteen, [[0; 6], [255; 6]].concat()),
+        ] {
+            let mut out = Vec::new();
+            write_png(&image, &mut out, depth).unwrap();
+
+            let decoder = png::Decoder::new(std::io::Cursor::new(out));
+            let mut reader = decoder.read_info().unwrap();
+            let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+            let info = reader.next_frame(&mut buf).unwrap();
+
+            assert!(2 == info.width);
+            assert!(1 == info.height);
+            assert!(expected == buf[..info.buffer_size()]);
+        }
+    }
+}
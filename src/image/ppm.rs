@@ -0,0 +1,62 @@
+//! Portable pixmap writers.
+
+use crate::image::Image;
+use std::io::{self, Write};
+
+/// Writes an ASCII (`P3`) portable pixmap.
+///
+/// Pixels are converted with the image's display transform and quantized to 8 bits.
+pub fn write_p3(image: &Image, out: impl Write) -> io::Result<()> {
+    let mut out = io::BufWriter::new(out);
+
+    writeln!(out, "P3\n{} {}\n255", image.width(), image.height())?;
+    for &pixel_color in image.pixels() {
+        let [r, g, b] = image.display().to_rgb8(pixel_color);
+        writeln!(out, "{r} {g} {b}")?;
+    }
+
+    out.flush()
+}
+
+/// Writes a binary (`P6`) portable pixmap.
+///
+/// Pixels are converted with the image's display transform and quantized to 8 bits.
+pub fn write_p6(image: &Image, out: impl Write) -> io::Result<()> {
+    let mut out = io::BufWriter::new(out);
+
+    write!(out, "P6\n{} {}\n255\n", image.width(), image.height())?;
+    for &pixel_color in image.pixels() {
+        out.write_all(&image.display().to_rgb8(pixel_color))?;
+    }
+
+    out.flush()
+}
+
+mod tests {
+    #[test]
+    fn p3() {
+        use super::*;
+        use crate::color::Color;
+
+        let image = Image::from_pixels(2, 1, vec![Color::BLACK, Color::WHITE]);
+
+        let mut out = Vec::new();
+        write_p3(&image, &mut out).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert!("P3\n2 1\n255\n0 0 0\n255 255 255\n" == text);
+    }
+
+    #[test]
+    fn p6() {
+        use super::*;
+        use crate::color::Color;
+
+        let image = Image::from_pixels(2, 1, vec![Color::BLACK, Color::WHITE]);
+
+        let mut out = Vec::new();
+        write_p6(&image, &mut out).unwrap();
+
+        assert!(b"P6\n2 1\n255\n\x00\x00\x00\xff\xff\xff" == out.as_slice());
+    }
+}
@@ -2,8 +2,8 @@
 //!
 //! Or define your scene in TOML using the [`scene_builder`].
 //!
-//! ```rust
-//! use rayt_rs::prelude::*;
+//! ```rust,no_run
+//! use rayt_rs::{image::ppm, prelude::*};
 //!
 //! const ASPECT_RATIO: f64 = 16.0 / 9.0;
 //!
@@ -35,7 +35,8 @@
 //!         .max_depth(50)
 //!         .build();
 //!
-//!     camera.render(world);
+//!     let image = camera.render(&world);
+//!     ppm::write_p3(&image, std::io::stdout().lock()).expect("failed write");
 //! }
 //! ```
 //! <div style="text-align: center;">
@@ -44,28 +45,59 @@
 //!
 //! </div>
 
+pub mod background;
+pub mod bvh;
 pub mod camera;
 pub mod color;
+pub mod environment;
 pub mod hittable;
+pub mod image;
+pub mod loaders;
 pub mod materials;
 pub mod math;
 pub mod ray;
 pub mod samplers;
 pub mod scene_builder;
+pub mod sky;
+pub mod tonemap;
 pub mod world;
 
 /// Commonly used items.
 pub mod prelude {
+    pub use crate::background::Background;
     pub use crate::camera::Camera;
+    pub use crate::camera::FisheyeMapping;
+    pub use crate::camera::Projection;
     pub use crate::color::Color;
+    pub use crate::environment::EnvironmentMap;
+    pub use crate::image::Image;
+    pub use crate::materials::Dielectric;
     pub use crate::materials::Diffuse;
+    pub use crate::materials::DiffuseLight;
+    pub use crate::materials::Fresnel;
     pub use crate::materials::Lambertian;
     pub use crate::materials::Metal;
     pub use crate::materials::Normals;
+    pub use crate::math::primitives::Capsule;
+    pub use crate::math::primitives::Cone;
+    pub use crate::math::primitives::Cuboid;
+    pub use crate::math::primitives::Cylinder;
+    pub use crate::math::primitives::Disk;
+    pub use crate::math::primitives::Plane;
+    pub use crate::math::primitives::Quad;
     pub use crate::math::primitives::Sphere;
+    pub use crate::math::primitives::Torus;
+    pub use crate::math::primitives::Triangle;
+    pub use crate::math::primitives::TriangleMesh;
+    pub use crate::math::transform::Transform;
+    pub use crate::math::transform::Transformed;
     pub use crate::math::types::Point3;
     pub use crate::math::types::UnitVec3;
     pub use crate::math::types::Vec3;
     pub use crate::samplers::SamplerConfig;
+    pub use crate::sky::PhysicalSky;
+    pub use crate::tonemap::DisplayTransform;
+    pub use crate::tonemap::ToneMap;
+    pub use crate::tonemap::TransferFunction;
     pub use crate::world::World;
 }
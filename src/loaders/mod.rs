@@ -0,0 +1,43 @@
+//! Mesh file loaders.
+
+use crate::math::primitives::MeshError;
+use std::{fmt, io};
+
+pub mod obj;
+pub mod ply;
+pub mod stl;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    /// A malformed line and why it could not be read.
+    Parse(usize, String),
+    /// Malformed binary data or an unsupported variant of a format.
+    Invalid(String),
+    Mesh(MeshError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "Mesh read error: {err}"),
+            LoadError::Parse(line, s) => write!(f, "Mesh parse error on line {line}: {s}"),
+            LoadError::Invalid(s) => write!(f, "Invalid mesh file: {s}"),
+            LoadError::Mesh(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> Self {
+        LoadError::Io(err)
+    }
+}
+
+impl From<MeshError> for LoadError {
+    fn from(err: MeshError) -> Self {
+        LoadError::Mesh(err)
+    }
+}
@@ -0,0 +1,548 @@
+//! Wavefront OBJ and MTL loading.
+//!
+//! Reads positions, normals, texture coordinates and polygonal faces, which
+//! are triangulated as fans. Each group or material change starts a new mesh.
+
+use crate::loaders::LoadError;
+use crate::math::types::{Point3, Vec3};
+use crate::{
+    color::Color,
+    materials::{Dielectric, DiffuseLight, Lambertian, MaterialId, Metal},
+    math::{
+        primitives::{MeshData, TriangleMesh},
+        transform::Transform,
+    },
+    world::World,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    str::SplitWhitespace,
+};
+
+/// A material from an MTL library.
+#[derive(Clone, PartialEq)]
+pub struct ObjMaterial {
+    pub name: String,
+    /// `Kd`.
+    pub diffuse: Color,
+    /// `Ks`.
+    pub specular: Color,
+    /// `Ke`.
+    pub emission: Color,
+    /// `Ni`, or that of glass when missing.
+    pub ior: f64,
+    /// `d`, or one minus `Tr`.
+    pub dissolve: f64,
+}
+
+impl ObjMaterial {
+    fn new(name: String) -> Self {
+        ObjMaterial {
+            name,
+            diffuse: Color::new(0.8, 0.8, 0.8),
+            specular: Color::BLACK,
+            emission: Color::BLACK,
+            ior: 1.5,
+            dissolve: 1.0,
+        }
+    }
+
+    /// Adds the closest matching material to the world.
+    ///
+    /// Emissive surfaces become [`DiffuseLight`], transparent surfaces
+    /// [`Dielectric`] with index `Ni`, surfaces whose specular color
+    /// outweighs their diffuse color [`Metal`], and everything else
+    /// [`Lambertian`].
+    pub fn add_to(&self, world: &mut World) -> MaterialId {
+        if self.emission.max_element() > 0.0 {
+            world.add_material(DiffuseLight::new(self.emission))
+        } else if self.dissolve < 1.0 {
+            world.add_material(Dielectric::new(self.ior))
+        } else if self.specular.max_element() > self.diffuse.max_element() {
+            world.add_material(Metal::new(self.specular))
+        } else {
+            world.add_material(Lambertian::new(self.diffuse))
+        }
+    }
+}
+
+/// A group of faces that share a material.
+pub struct ObjMesh {
+    /// The group or object name.
+    pub name: String,
+    /// The `usemtl` material name.
+    pub material: Option<String>,
+    pub data: MeshData,
+}
+
+/// The meshes and materials of an OBJ file.
+pub struct ObjModel {
+    pub meshes: Vec<ObjMesh>,
+    pub materials: Vec<ObjMaterial>,
+}
+
+impl ObjModel {
+    /// Loads an OBJ file and the MTL libraries it references.
+    ///
+    /// Libraries are resolved relative to the OBJ file. Missing libraries are
+    /// logged and their materials left undefined.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let (mut model, libraries) = parse(BufReader::new(File::open(path)?))?;
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for library in libraries {
+            match File::open(dir.join(&library)) {
+                Ok(file) => model.materials.extend(parse_mtl(BufReader::new(file))?),
+                Err(err) => log::warn!("Skipping material library {library}: {err}"),
+            }
+        }
+
+        Ok(model)
+    }
+
+    /// Adds every mesh to the world, along with the materials they use.
+    ///
+    /// Meshes use their MTL material when it is defined and `default`
+    /// otherwise.
+    pub fn add_to(
+        self,
+        world: &mut World,
+        default: MaterialId,
+        transform: &Transform,
+    ) -> Result<(), LoadError> {
+        // Libraries can hold many more materials than the model uses.
+        let used: HashSet<&str> = self
+            .meshes
+            .iter()
+            .filter_map(|mesh| mesh.material.as_deref())
+            .collect();
+        let ids: HashMap<String, MaterialId> = self
+            .materials
+            .iter()
+            .filter(|m| used.contains(m.name.as_str()))
+            .map(|m| (m.name.clone(), m.add_to(world)))
+            .collect();
+
+        let affine = transform.to_affine();
+        for mut mesh in self.meshes {
+            let material = mesh
+                .material
+                .and_then(|name| ids.get(&name).copied())
+                .unwrap_or(default);
+
+            if !transform.is_identity() {
+                mesh.data.transform(affine);
+            }
+
+            world.add_object(TriangleMesh::new(mesh.data, material)?);
+        }
+
+        Ok(())
+    }
+}
+
+/// A vertex by its position, texture coordinate and normal indices.
+type VertexKey = (usize, Option<usize>, Option<usize>);
+
+/// Accumulates the faces of one mesh.
+struct MeshBuilder {
+    name: String,
+    material: Option<String>,
+    data: MeshData,
+    vertices: HashMap<VertexKey, usize>,
+    normals: Vec<Vec3>,
+    uvs: Vec<[f64; 2]>,
+    all_normals: bool,
+    all_uvs: bool,
+}
+
+impl MeshBuilder {
+    fn new(name: String, material: Option<String>) -> Self {
+        MeshBuilder {
+            name,
+            material,
+            data: MeshData::default(),
+            vertices: HashMap::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            all_normals: true,
+            all_uvs: true,
+        }
+    }
+
+    fn vertex(&mut self, key: VertexKey, attributes: &Attributes) -> usize {
+        *self.vertices.entry(key).or_insert_with(|| {
+            let (p, uv, n) = key;
+            self.data.positions.push(attributes.positions[p]);
+            self.normals
+                .push(n.map_or(Vec3::ZERO, |n| attributes.normals[n]));
+            self.uvs.push(uv.map_or([0.0; 2], |uv| attributes.uvs[uv]));
+            self.all_normals &= n.is_some();
+            self.all_uvs &= uv.is_some();
+            self.data.positions.len() - 1
+        })
+    }
+
+    fn build(mut self) -> Option<ObjMesh> {
+        if self.data.indices.is_empty() {
+            return None;
+        }
+
+        self.data.normals = self.all_normals.then_some(self.normals);
+        self.data.uvs = self.all_uvs.then_some(self.uvs);
+
+        Some(ObjMesh {
+            name: self.name,
+            material: self.material,
+            data: self.data,
+        })
+    }
+}
+
+/// The vertex attributes shared by every mesh in a file.
+#[derive(Default)]
+struct Attributes {
+    positions: Vec<Point3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<[f64; 2]>,
+}
+
+/// Parses an OBJ file, returning the model without materials and the names
+/// of its MTL libraries.
+fn parse(reader: impl BufRead) -> Result<(ObjModel, Vec<String>), LoadError> {
+    let mut attributes = Attributes::default();
+    let mut meshes = Vec::new();
+    let mut libraries = Vec::new();
+    let mut builder = MeshBuilder::new(String::new(), None);
+    let mut face = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = i + 1;
+        let err = |s: String| LoadError::Parse(number, s);
+
+        let mut tokens = line.split('#').next().unwrap_or("").split_whitespace();
+        match tokens.next() {
+            Some("v") => attributes
+                .positions
+                .push(Point3::from(floats::<3>(&mut tokens).map_err(err)?)),
+            Some("vn") => attributes
+                .normals
+                .push(Vec3::from(floats::<3>(&mut tokens).map_err(err)?)),
+            Some("vt") => {
+                // v defaults to zero and the optional third coordinate is ignored.
+                let [u] = floats::<1>(&mut tokens).map_err(err)?;
+                let v = match tokens.next() {
+                    Some(token) => token
+                        .parse()
+                        .map_err(|_| err(format!("invalid number {token:?}")))?,
+                    None => 0.0,
+                };
+                attributes.uvs.push([u, v]);
+            }
+            Some("f") => {
+                face.clear();
+                for token in tokens {
+                    let key = vertex_key(token, &attributes).map_err(err)?;
+                    face.push(builder.vertex(key, &attributes));
+                }
+
+                if face.len() < 3 {
+                    return Err(err(format!("face with {} vertices", face.len())));
+                }
+
+                for k in 1..face.len() - 1 {
+                    builder.data.indices.push([face[0], face[k], face[k + 1]]);
+                }
+            }
+            Some("g" | "o") => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                let material = builder.material.clone();
+                meshes.extend(
+                    std::mem::replace(&mut builder, MeshBuilder::new(name, material)).build(),
+                );
+            }
+            Some("usemtl") => {
+                let material = Some(tokens.collect::<Vec<_>>().join(" "));
+                if material != builder.material {
+                    let name = builder.name.clone();
+                    meshes.extend(
+                        std::mem::replace(&mut builder, MeshBuilder::new(name, material)).build(),
+                    );
+                }
+            }
+            Some("mtllib") => libraries.extend(tokens.map(String::from)),
+            _ => {}
+        }
+    }
+    meshes.extend(builder.build());
+
+    let model = ObjModel {
+        meshes,
+        materials: Vec::new(),
+    };
+    Ok((model, libraries))
+}
+
+/// Parses an MTL library.
+fn parse_mtl(reader: impl BufRead) -> Result<Vec<ObjMaterial>, LoadError> {
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = i + 1;
+        let err = |s: String| LoadError::Parse(number, s);
+
+        let mut tokens = line.split('#').next().unwrap_or("").split_whitespace();
+        let keyword = tokens.next();
+
+        if keyword == Some("newmtl") {
+            let name = tokens.collect::<Vec<_>>().join(" ");
+            materials.push(ObjMaterial::new(name));
+            continue;
+        }
+
+        let Some(material) = materials.last_mut() else {
+            continue;
+        };
+
+        match keyword {
+            Some("Kd") => material.diffuse = color(&mut tokens).map_err(err)?,
+            Some("Ks") => material.specular = color(&mut tokens).map_err(err)?,
+            Some("Ke") => material.emission = color(&mut tokens).map_err(err)?,
+            Some("Ni") => material.ior = floats::<1>(&mut tokens).map_err(err)?[0],
+            Some("d") => material.dissolve = floats::<1>(&mut tokens).map_err(err)?[0],
+            Some("Tr") => material.dissolve = 1.0 - floats::<1>(&mut tokens).map_err(err)?[0],
+            _ => {}
+        }
+    }
+
+    Ok(materials)
+}
+
+/// Parses the next `N` numbers.
+fn floats<const N: usize>(tokens: &mut SplitWhitespace) -> Result<[f64; N], String> {
+    let mut values = [0.0; N];
+    for value in &mut values {
+        let token = tokens.next().ok_or("missing number")?;
+        *value = token
+            .parse()
+            .map_err(|_| format!("invalid number {token:?}"))?;
+    }
+    Ok(values)
+}
+
+/// Parses an RGB color, where a single value is gray.
+fn color(tokens: &mut SplitWhitespace) -> Result<Color, String> {
+    let values = tokens
+        .map(|token| {
+            token
+                .parse()
+                .map_err(|_| format!("invalid number {token:?}"))
+        })
+        .collect::<Result<Vec<f64>, _>>()?;
+
+    match values[..] {
+        [gray] => Ok(Color::new(gray, gray, gray)),
+        [r, g, b] => Ok(Color::new(r, g, b)),
+        _ => Err(format!("expected 1 or 3 numbers, found {}", values.len())),
+    }
+}
+
+/// Resolves a `v`, `v/vt`, `v//vn` or `v/vt/vn` face vertex to zero-based indices.
+fn vertex_key(token: &str, attributes: &Attributes) -> Result<VertexKey, String> {
+    let mut parts = token.split('/');
+
+    let index = |part: Option<&str>, len: usize| -> Result<Option<usize>, String> {
+        let Some(part) = part.filter(|s| !s.is_empty()) else {
+            return Ok(None);
+        };
+
+        let i: isize = part
+            .parse()
+            .map_err(|_| format!("invalid index {part:?}"))?;
+
+        // One-based, or relative to the end when negative.
+        let resolved = match i {
+            1.. => i - 1,
+            ..0 => len as isize + i,
+            0 => return Err("index 0".to_string()),
+        };
+
+        if resolved < 0 || resolved as usize >= len {
+            return Err(format!("index {i} out of range for {len} elements"));
+        }
+
+        Ok(Some(resolved as usize))
+    };
+
+    let p = index(parts.next(), attributes.positions.len())?.ok_or("missing position index")?;
+    let uv = index(parts.next(), attributes.uvs.len())?;
+    let n = index(parts.next(), attributes.normals.len())?;
+
+    Ok((p, uv, n))
+}
+
+mod tests {
+    #[test]
+    fn triangulates_polygons() {
+        use super::*;
+
+        let obj = "
+            # A unit square and a pentagon.
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 1 0
+            v 0.5 1.5 0
+            f 1 2 3 4
+            f -5 -4 -3 5 -2
+        ";
+
+        let (model, libraries) = parse(obj.as_bytes()).unwrap();
+        assert!(libraries.is_empty());
+        assert!(1 == model.meshes.len());
+
+        let data = &model.meshes[0].data;
+        assert!(5 == data.positions.len());
+        assert!(vec![[0, 1, 2], [0, 2, 3], [0, 1, 2], [0, 2, 4], [0, 4, 3]] == data.indices);
+        assert!(data.normals.is_none() && data.uvs.is_none());
+    }
+
+    #[test]
+    fn attributes_and_groups() {
+        use super::*;
+
+        let obj = "
+            mtllib scene.mtl
+            v 0 0 0
+            v 1 0 0
+            v 0 1 0
+            vt 0 0
+            vt 1 0
+            vt 0 1 0
+            vn 0 0 1
+            g first
+            usemtl red
+            f 1/1/1 2/2/1 3/3/1
+            usemtl blue
+            f 1//1 3//1 2//1
+            g second
+            f 1/1 2/2 3/3
+        ";
+
+        let (model, libraries) = parse(obj.as_bytes()).unwrap();
+        assert!(vec!["scene.mtl".to_string()] == libraries);
+
+        let meshes = &model.meshes;
+        assert!(3 == meshes.len());
+
+        assert!("first" == meshes[0].name && Some("red") == meshes[0].material.as_deref());
+        assert!(Some(vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]) == meshes[0].data.uvs);
+        assert!(Some(vec![Vec3::Z; 3]) == meshes[0].data.normals);
+
+        assert!("first" == meshes[1].name && Some("blue") == meshes[1].material.as_deref());
+        assert!(meshes[1].data.uvs.is_none() && meshes[1].data.normals.is_some());
+
+        assert!("second" == meshes[2].name && Some("blue") == meshes[2].material.as_deref());
+        assert!(meshes[2].data.uvs.is_some() && meshes[2].data.normals.is_none());
+    }
+
+    #[test]
+    fn one_coordinate_uvs() {
+        use super::*;
+
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0.25\nvt 0.5\nvt 1 1\nf 1/1 2/2 3/3\n";
+        let (model, _) = parse(obj.as_bytes()).unwrap();
+        let uvs = Some(vec![[0.25, 0.0], [0.5, 0.0], [1.0, 1.0]]);
+        assert!(uvs == model.meshes[0].data.uvs);
+
+        assert!(parse("vt\n".as_bytes()).is_err());
+        assert!(parse("vt 0 x\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn invalid_index() {
+        use super::*;
+
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\n";
+        let err = parse(obj.as_bytes()).err().unwrap();
+        assert!(
+            "Mesh parse error on line 4: index 4 out of range for 3 elements" == err.to_string()
+        );
+    }
+
+    #[test]
+    fn parse_materials() {
+        use super::*;
+
+        let mtl = "
+            newmtl matte
+            Kd 0.5 0.25 0.125
+            Ks 0.1 0.1 0.1
+
+            newmtl mirror
+            Kd 0
+            Ks 0.9
+            Ni 1.5
+            Ke 1 2 3
+            Tr 0.25
+        ";
+
+        let materials = parse_mtl(mtl.as_bytes()).unwrap();
+        assert!(2 == materials.len());
+
+        assert!("matte" == materials[0].name);
+        assert!(Color::new(0.5, 0.25, 0.125) == materials[0].diffuse);
+        assert!(Color::new(0.1, 0.1, 0.1) == materials[0].specular);
+
+        assert!("mirror" == materials[1].name);
+        assert!(Color::new(0.9, 0.9, 0.9) == materials[1].specular);
+        assert!(Color::new(1.0, 2.0, 3.0) == materials[1].emission);
+        assert!(1.5 == materials[1].ior);
+        assert!(1.0 == materials[0].dissolve && 0.75 == materials[1].dissolve);
+    }
+
+    #[test]
+    fn transparent_without_ior() {
+        use super::*;
+
+        // An index of one would make the surface invisible.
+        let materials = parse_mtl("newmtl glass\nd 0.5\n".as_bytes()).unwrap();
+        assert!(0.5 == materials[0].dissolve);
+        assert!(1.5 == materials[0].ior);
+    }
+
+    #[test]
+    fn adds_used_materials() {
+        use super::*;
+
+        let data = MeshData {
+            positions: vec![Point3::ZERO, Point3::X, Point3::Y],
+            indices: vec![[0, 1, 2]],
+            ..Default::default()
+        };
+        let mesh = |material: Option<&str>| ObjMesh {
+            name: String::new(),
+            material: material.map(str::to_string),
+            data: data.clone(),
+        };
+        let model = ObjModel {
+            meshes: vec![mesh(Some("used")), mesh(Some("undefined")), mesh(None)],
+            materials: ["used", "unused"]
+                .map(|n| ObjMaterial::new(n.to_string()))
+                .to_vec(),
+        };
+
+        let mut world = World::default();
+        let default = world.add_material(Lambertian::new(Color::WHITE));
+        model
+            .add_to(&mut world, default, &Transform::new())
+            .unwrap();
+        assert!(2 == world.materials().len());
+    }
+}
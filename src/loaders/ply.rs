@@ -0,0 +1,368 @@
+//! Stanford PLY loading.
+//!
+//! Reads ASCII and binary little-endian files. Vertices provide positions and
+//! optionally normals (`nx`, `ny`, `nz`) and texture coordinates (`u`, `v` or
+//! `s`, `t`). Polygonal faces are triangulated as fans and other elements are
+//! skipped.
+
+use crate::loaders::LoadError;
+use crate::math::primitives::MeshData;
+use crate::math::types::{Point3, Vec3};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+#[derive(Copy, Clone, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+}
+
+#[derive(Copy, Clone)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Result<Self, LoadError> {
+        Ok(match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return Err(invalid(format!("unknown property type {name:?}"))),
+        })
+    }
+}
+
+enum Property {
+    Scalar(Scalar),
+    /// The count type and the item type.
+    List(Scalar, Scalar),
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<(String, Property)>,
+}
+
+impl Element {
+    fn position(&self, names: &[&str]) -> Option<usize> {
+        self.properties
+            .iter()
+            .position(|(name, _)| names.contains(&name.as_str()))
+    }
+}
+
+/// Loads a PLY file.
+pub fn load(path: impl AsRef<Path>) -> Result<MeshData, LoadError> {
+    parse(BufReader::new(File::open(path)?))
+}
+
+fn parse(mut reader: impl BufRead) -> Result<MeshData, LoadError> {
+    let (format, elements) = parse_header(&mut reader)?;
+
+    let mut text = String::new();
+    let mut body = match format {
+        Format::Ascii => {
+            reader.read_to_string(&mut text)?;
+            Body::Ascii(text.split_ascii_whitespace())
+        }
+        Format::BinaryLittleEndian => Body::Binary(Box::new(reader)),
+    };
+
+    let vertex_count = elements
+        .iter()
+        .find(|e| e.name == "vertex")
+        .map_or(0, |e| e.count);
+
+    let mut data = MeshData::default();
+    let mut values = Vec::new();
+    let mut list = Vec::new();
+
+    for element in &elements {
+        let xyz = ["x", "y", "z"].map(|n| element.position(&[n]));
+        let normal = ["nx", "ny", "nz"].map(|n| element.position(&[n]));
+        let uv = [
+            element.position(&["u", "s", "texture_u", "texture_s"]),
+            element.position(&["v", "t", "texture_v", "texture_t"]),
+        ];
+        let indices = element.position(&["vertex_indices", "vertex_index"]);
+
+        let is_vertex = element.name == "vertex";
+        let is_face = element.name == "face";
+
+        if is_vertex {
+            let [Some(x), Some(y), Some(z)] = xyz else {
+                return Err(invalid("vertex without x, y and z".to_string()));
+            };
+            let has_normals = normal.iter().all(Option::is_some);
+            let has_uvs = uv.iter().all(Option::is_some);
+
+            data.positions.reserve(element.count);
+            for _ in 0..element.count {
+                values.clear();
+                for (_, property) in &element.properties {
+                    match *property {
+                        Property::Scalar(scalar) => values.push(body.read(scalar)?),
+                        Property::List(count, item) => {
+                            values.push(0.0);
+                            body.read_list(count, item, &mut list)?;
+                        }
+                    }
+                }
+
+                data.positions
+                    .push(Point3::new(values[x], values[y], values[z]));
+                if has_normals {
+                    let [nx, ny, nz] = normal.map(|i| values[i.unwrap()]);
+                    data.normals
+                        .get_or_insert_default()
+                        .push(Vec3::new(nx, ny, nz));
+                }
+                if has_uvs {
+                    let [u, v] = uv.map(|i| values[i.unwrap()]);
+                    data.uvs.get_or_insert_default().push([u, v]);
+                }
+            }
+        } else {
+            let indices = indices.filter(|_| is_face);
+            let mut face = Vec::new();
+
+            for _ in 0..element.count {
+                for (i, (_, property)) in element.properties.iter().enumerate() {
+                    match *property {
+                        Property::Scalar(scalar) => {
+                            body.read(scalar)?;
+                        }
+                        Property::List(count, item) => {
+                            body.read_list(count, item, &mut list)?;
+                            if Some(i) == indices {
+                                face.clone_from(&list);
+                            }
+                        }
+                    }
+                }
+
+                if indices.is_none() {
+                    continue;
+                }
+
+                if face.len() < 3 {
+                    return Err(invalid(format!("face with {} vertices", face.len())));
+                }
+
+                // Indices are read as floats, so check them before casting.
+                let face = face
+                    .iter()
+                    .map(|&i| {
+                        if i >= 0.0 && i.fract() == 0.0 && i < vertex_count as f64 {
+                            Ok(i as usize)
+                        } else {
+                            Err(invalid(format!(
+                                "vertex index {i} out of range for {vertex_count} vertices"
+                            )))
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                for k in 1..face.len() - 1 {
+                    data.indices.push([face[0], face[k], face[k + 1]]);
+                }
+            }
+        }
+    }
+
+    Ok(data)
+}
+
+/// Parses the header up to and including `end_header`.
+fn parse_header(reader: &mut impl BufRead) -> Result<(Format, Vec<Element>), LoadError> {
+    let mut line = String::new();
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+
+    for number in 1.. {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(invalid("missing end_header".to_string()));
+        }
+        let err = |s: String| LoadError::Parse(number, s);
+
+        let mut tokens = line.split_whitespace();
+        let keyword = tokens.next();
+
+        if number == 1 {
+            if keyword != Some("ply") {
+                return Err(err("missing ply magic".to_string()));
+            }
+            continue;
+        }
+
+        match keyword {
+            Some("format") => {
+                format = Some(match tokens.next() {
+                    Some("ascii") => Format::Ascii,
+                    Some("binary_little_endian") => Format::BinaryLittleEndian,
+                    other => return Err(err(format!("unsupported format {other:?}"))),
+                });
+            }
+            Some("element") => {
+                let (Some(name), Some(count)) = (tokens.next(), tokens.next()) else {
+                    return Err(err("expected element name and count".to_string()));
+                };
+                let count = count
+                    .parse()
+                    .map_err(|_| err(format!("invalid count {count:?}")))?;
+                elements.push(Element {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            Some("property") => {
+                let Some(element) = elements.last_mut() else {
+                    return Err(err("property before element".to_string()));
+                };
+                let tokens: Vec<&str> = tokens.collect();
+                let (name, property) = match tokens[..] {
+                    ["list", count, item, name] => (
+                        name,
+                        Property::List(Scalar::parse(count)?, Scalar::parse(item)?),
+                    ),
+                    [scalar, name] => (name, Property::Scalar(Scalar::parse(scalar)?)),
+                    _ => return Err(err("malformed property".to_string())),
+                };
+                element.properties.push((name.to_string(), property));
+            }
+            Some("end_header") => break,
+            _ => {}
+        }
+    }
+
+    let format = format.ok_or_else(|| invalid("missing format".to_string()))?;
+    Ok((format, elements))
+}
+
+/// The element data after the header.
+enum Body<'a> {
+    Ascii(std::str::SplitAsciiWhitespace<'a>),
+    Binary(Box<dyn Read + 'a>),
+}
+
+impl Body<'_> {
+    fn read(&mut self, scalar: Scalar) -> Result<f64, LoadError> {
+        match self {
+            Body::Ascii(tokens) => {
+                let token = tokens
+                    .next()
+                    .ok_or_else(|| invalid("unexpected end of data".to_string()))?;
+                token
+                    .parse()
+                    .map_err(|_| invalid(format!("invalid number {token:?}")))
+            }
+            Body::Binary(reader) => {
+                macro_rules! read {
+                    ($t:ty) => {{
+                        let mut bytes = [0; size_of::<$t>()];
+                        reader.read_exact(&mut bytes)?;
+                        <$t>::from_le_bytes(bytes) as f64
+                    }};
+                }
+
+                Ok(match scalar {
+                    Scalar::I8 => read!(i8),
+                    Scalar::U8 => read!(u8),
+                    Scalar::I16 => read!(i16),
+                    Scalar::U16 => read!(u16),
+                    Scalar::I32 => read!(i32),
+                    Scalar::U32 => read!(u32),
+                    Scalar::F32 => read!(f32),
+                    Scalar::F64 => read!(f64),
+                })
+            }
+        }
+    }
+
+    fn read_list(
+        &mut self,
+        count: Scalar,
+        item: Scalar,
+        list: &mut Vec<f64>,
+    ) -> Result<(), LoadError> {
+        let n = self.read(count)? as usize;
+        list.clear();
+        for _ in 0..n {
+            list.push(self.read(item)?);
+        }
+        Ok(())
+    }
+}
+
+fn invalid(s: String) -> LoadError {
+    LoadError::Invalid(s)
+}
+
+mod tests {
+    #[test]
+    fn ascii() {
+        use super::*;
+
+        let data = parse(&include_bytes!("fixtures/square_ascii.ply")[..]).unwrap();
+        assert!(4 == data.positions.len());
+        assert!(Point3::new(1.0, 1.0, 0.0) == data.positions[2]);
+        assert!(Some(vec![Vec3::Z; 4]) == data.normals);
+        assert!(Some([1.0, 1.0]) == data.uvs.as_ref().map(|uv| uv[2]));
+        assert!(vec![[0, 1, 2], [0, 2, 3]] == data.indices);
+    }
+
+    #[test]
+    fn binary_little_endian() {
+        use super::*;
+
+        let data = parse(&include_bytes!("fixtures/tetrahedron_le.ply")[..]).unwrap();
+        assert!(4 == data.positions.len());
+        assert!(Point3::new(0.0, 0.0, 1.0) == data.positions[3]);
+        assert!(data.normals.is_none() && data.uvs.is_none());
+        assert!(vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]] == data.indices);
+        assert!(data.validate().is_ok());
+    }
+
+    #[test]
+    fn big_endian_is_rejected() {
+        use super::*;
+
+        let ply = "ply\nformat binary_big_endian 1.0\nend_header\n";
+        let err = parse(ply.as_bytes()).err().unwrap();
+        assert!(err.to_string().contains("unsupported format"));
+    }
+
+    #[test]
+    fn bad_indices_are_rejected() {
+        use super::*;
+
+        let header = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\n\
+            property float y\nproperty float z\nelement face 1\n\
+            property list uchar int vertex_indices\nend_header\n\
+            0 0 0\n1 0 0\n0 1 0\n";
+
+        for face in ["3 0 1 -1", "3 0 1.5 2", "3 0 1 3"] {
+            let err = parse(format!("{header}{face}\n").as_bytes()).err().unwrap();
+            assert!(err.to_string().contains("out of range"));
+        }
+        assert!(parse(format!("{header}3 0 1 2\n").as_bytes()).is_ok());
+    }
+}
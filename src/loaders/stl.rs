@@ -0,0 +1,99 @@
+//! Binary STL loading.
+//!
+//! Vertices shared between facets are merged. Facet normals are ignored in
+//! favor of the winding order, so the mesh is flat shaded.
+
+use crate::loaders::LoadError;
+use crate::math::primitives::MeshData;
+use crate::math::types::Point3;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+/// The size of the header before the facet count.
+const HEADER_LEN: usize = 80;
+/// The size of one facet: a normal, three vertices and an attribute count.
+const FACET_LEN: usize = 50;
+
+/// Loads a binary STL file.
+pub fn load(path: impl AsRef<Path>) -> Result<MeshData, LoadError> {
+    parse(BufReader::new(File::open(path)?))
+}
+
+fn parse(mut reader: impl Read) -> Result<MeshData, LoadError> {
+    let mut header = [0; HEADER_LEN + 4];
+    reader.read_exact(&mut header).map_err(|_| truncated())?;
+
+    let count = u32::from_le_bytes(header[HEADER_LEN..].try_into().unwrap()) as usize;
+
+    let mut data = MeshData::default();
+    let mut vertices: HashMap<[u32; 3], usize> = HashMap::new();
+    let mut facet = [0; FACET_LEN];
+
+    for _ in 0..count {
+        reader.read_exact(&mut facet).map_err(|_| {
+            if header.starts_with(b"solid") {
+                LoadError::Invalid("ASCII STL is not supported".to_string())
+            } else {
+                truncated()
+            }
+        })?;
+
+        let mut triangle = [0; 3];
+        for (k, index) in triangle.iter_mut().enumerate() {
+            // Skip the normal.
+            let offset = 12 * (k + 1);
+            let bits: [u32; 3] = std::array::from_fn(|i| {
+                let start = offset + 4 * i;
+                u32::from_le_bytes(facet[start..start + 4].try_into().unwrap())
+            });
+
+            *index = *vertices.entry(bits).or_insert_with(|| {
+                let [x, y, z] = bits.map(|b| f32::from_bits(b) as f64);
+                data.positions.push(Point3::new(x, y, z));
+                data.positions.len() - 1
+            });
+        }
+
+        // Facets collapsed by merging are dropped.
+        let [a, b, c] = triangle;
+        if a != b && b != c && a != c {
+            data.indices.push(triangle);
+        }
+    }
+
+    Ok(data)
+}
+
+fn truncated() -> LoadError {
+    LoadError::Invalid("truncated STL data".to_string())
+}
+
+mod tests {
+    #[test]
+    fn binary() {
+        use super::*;
+
+        let data = parse(&include_bytes!("fixtures/tetrahedron.stl")[..]).unwrap();
+        assert!(4 == data.positions.len());
+        assert!(4 == data.indices.len());
+        assert!(data.normals.is_none() && data.uvs.is_none());
+
+        let p = &data.positions;
+        assert!(Point3::new(0.0, 0.0, 0.0) == p[data.indices[0][0]]);
+        assert!(Point3::new(0.0, 1.0, 0.0) == p[data.indices[0][1]]);
+        assert!(Point3::new(1.0, 0.0, 0.0) == p[data.indices[0][2]]);
+    }
+
+    #[test]
+    fn ascii_is_rejected() {
+        use super::*;
+
+        let stl = format!("solid cube{}", " ".repeat(200));
+        let err = parse(stl.as_bytes()).err().unwrap();
+        assert!("Invalid mesh file: ASCII STL is not supported" == err.to_string());
+    }
+}
@@ -1,8 +1,9 @@
-use rayt_rs::prelude::*;
+use rayt_rs::{image::ppm, prelude::*};
+use std::io;
 
 const ASPECT_RATIO: f64 = 16.0 / 9.0;
 
-fn main() {
+fn main() -> anyhow::Result<()> {
     // Safety: logging
     unsafe { std::env::set_var("RUST_LOG", "info") }
     env_logger::init();
@@ -30,5 +31,11 @@ fn main() {
         .max_depth(50)
         .build();
 
-    camera.render(world);
+    let image = camera.render(&world);
+    match std::env::args().nth(1) {
+        Some(path) => image.save(path)?,
+        None => ppm::write_p3(&image, io::stdout().lock())?,
+    }
+
+    Ok(())
 }
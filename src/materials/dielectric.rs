@@ -0,0 +1,225 @@
+use crate::{
+    color::Color,
+    hittable::{FrontFace, HitRecord},
+    materials::Material,
+    ray::Ray,
+};
+use rand::Rng;
+
+/// How the fraction of reflected light is computed.
+#[derive(Copy, Clone, Default, PartialEq, Debug, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Fresnel {
+    /// Schlick's approximation.
+    #[default]
+    Schlick,
+    /// The unpolarized Fresnel equations.
+    Exact,
+}
+
+impl Fresnel {
+    /// Returns the reflectance for the cosine of the incident angle and the
+    /// ratio of the incident to the transmitted index of refraction.
+    pub fn reflectance(self, cos_theta: f64, eta: f64) -> f64 {
+        match self {
+            Fresnel::Schlick => {
+                let r0 = ((1.0 - eta) / (1.0 + eta)).powi(2);
+                r0 + (1.0 - r0) * (1.0 - cos_theta).powi(5)
+            }
+            Fresnel::Exact => {
+                let sin2_t = eta * eta * (1.0 - cos_theta * cos_theta);
+                if sin2_t >= 1.0 {
+                    return 1.0;
+                }
+                let cos_t = (1.0 - sin2_t).sqrt();
+                let r_s = (eta * cos_theta - cos_t) / (eta * cos_theta + cos_t);
+                let r_p = (eta * cos_t - cos_theta) / (eta * cos_t + cos_theta);
+                (r_s * r_s + r_p * r_p) / 2.0
+            }
+        }
+    }
+}
+
+/// A clear material, like glass or water, that reflects and refracts.
+#[derive(serde::Deserialize)]
+pub struct Dielectric {
+    /// The index of refraction relative to the surrounding medium.
+    ior: f64,
+    #[serde(default)]
+    fresnel: Fresnel,
+    /// Optional absorption per unit distance travelled inside.
+    #[serde(default)]
+    absorption: Option<Color>,
+}
+
+impl Dielectric {
+    pub fn new(ior: f64) -> Self {
+        Dielectric {
+            ior,
+            fresnel: Fresnel::Schlick,
+            absorption: None,
+        }
+    }
+
+    pub fn fresnel(mut self, fresnel: Fresnel) -> Self {
+        self.fresnel = fresnel;
+        self
+    }
+
+    /// Tints light by `exp(-absorption * distance)` as it passes through.
+    pub fn absorption(mut self, absorption: Color) -> Self {
+        self.absorption = Some(absorption);
+        self
+    }
+}
+
+impl Material for Dielectric {
+    fn scatter(&self, ray: &Ray, record: &HitRecord) -> Option<(Color, Ray)> {
+        let inside = matches!(record.front_face, FrontFace::Inside);
+        let eta = if inside { self.ior } else { 1.0 / self.ior };
+
+        let direction = *ray.direction();
+        let normal = *record.normal;
+        let cos_theta = (-direction).dot(normal).min(1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+
+        let total_internal_reflection = eta * sin_theta > 1.0;
+        let reflectance = self.fresnel.reflectance(cos_theta, eta);
+
+        let scattered_direction =
+            if total_internal_reflection || rand::rng().random::<f64>() < reflectance {
+                direction.reflect(normal)
+            } else {
+                // Snell's law, split into parts perpendicular and parallel to the normal.
+                let perpendicular = eta * (direction + cos_theta * normal);
+                let parallel = -(1.0 - perpendicular.length_squared()).abs().sqrt() * normal;
+                perpendicular + parallel
+            };
+
+        // The ray travelled `t` through the medium to reach this exit point.
+        let attenuation = match self.absorption {
+            Some(absorption) if inside => Color((-*absorption * record.t).exp()),
+            _ => Color::WHITE,
+        };
+
+        let scattered_ray = Ray::with_time(record.hit_point, scattered_direction, ray.time());
+        Some((attenuation, scattered_ray))
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use crate::materials::{MaterialId, TomlMaterial};
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [[material.dielectric]]
+            id = 1
+            ior = 1.5
+
+            [[material.dielectric]]
+            id = 2
+            ior = 1.33
+            fresnel = "exact"
+            absorption = [0.5, 0.1, 0.0]
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            material: MaterialConfig,
+        }
+
+        #[derive(Deserialize)]
+        struct MaterialConfig {
+            dielectric: Vec<TomlMaterial<Dielectric>>,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let [glass, water] = &config.material.dielectric[..] else {
+            panic!("expected two materials");
+        };
+        assert!(MaterialId(1) == glass.id);
+        assert!(1.5 == glass.data.ior);
+        assert!(Fresnel::Schlick == glass.data.fresnel);
+        assert!(glass.data.absorption.is_none());
+
+        assert!(Fresnel::Exact == water.data.fresnel);
+        assert!(Some(Color::new(0.5, 0.1, 0.0)) == water.data.absorption);
+    }
+
+    #[test]
+    fn fresnel() {
+        use super::*;
+
+        // Head on into glass both reflect ((1 - 1.5) / (1 + 1.5))^2.
+        for fresnel in [Fresnel::Schlick, Fresnel::Exact] {
+            assert!((fresnel.reflectance(1.0, 1.0 / 1.5) - 0.04).abs() < 1e-12);
+            assert!((fresnel.reflectance(0.0, 1.0 / 1.5) - 1.0).abs() < 1e-12);
+        }
+
+        // Past the critical angle inside glass.
+        assert!(1.0 == Fresnel::Exact.reflectance(0.5, 1.5));
+
+        // Schlick stays close to the exact curve.
+        for i in 0..=10 {
+            let cos_theta = i as f64 / 10.0;
+            let (a, b) = (
+                Fresnel::Schlick.reflectance(cos_theta, 1.0 / 1.5),
+                Fresnel::Exact.reflectance(cos_theta, 1.0 / 1.5),
+            );
+            assert!((a - b).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn refraction() {
+        use super::*;
+        use crate::materials::MaterialId;
+        use crate::math::types::{Point3, UnitVec3, Vec3};
+
+        let glass = Dielectric::new(1.5).absorption(Color::new(1.0, 0.0, 0.0));
+
+        // 45 degrees onto glass facing +y.
+        let ray = Ray::new(Point3::new(-1.0, 1.0, 0.0), Vec3::new(1.0, -1.0, 0.0));
+        let record = HitRecord::new(
+            &ray,
+            2f64.sqrt(),
+            Point3::ZERO,
+            UnitVec3::from(Vec3::Y),
+            MaterialId(0),
+        );
+
+        let sin_t = 45f64.to_radians().sin() / 1.5;
+        let mut refracted = 0;
+        for _ in 0..100 {
+            let (attenuation, scattered) = glass.scatter(&ray, &record).unwrap();
+            assert!(Color::WHITE == attenuation);
+
+            let d = scattered.direction();
+            if d.y < 0.0 {
+                refracted += 1;
+                assert!((d.x - sin_t).abs() < 1e-12);
+            } else {
+                assert!(d.abs_diff_eq(Vec3::new(1.0, 1.0, 0.0).normalize(), 1e-12));
+            }
+        }
+        assert!(refracted > 80);
+
+        // Leaving at a grazing angle reflects, absorbed over the path inside.
+        let ray = Ray::new(Point3::new(-1.0, -0.1, 0.0), Vec3::new(1.0, 0.1, 0.0));
+        let record = HitRecord::new(
+            &ray,
+            2.0,
+            Point3::ZERO,
+            UnitVec3::from(Vec3::Y),
+            MaterialId(0),
+        );
+        assert!(matches!(record.front_face, FrontFace::Inside));
+
+        let (attenuation, scattered) = glass.scatter(&ray, &record).unwrap();
+        assert!(scattered.direction().y < 0.0);
+        assert!(Color::new((-2f64).exp(), 1.0, 1.0) == attenuation);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::math::types::UnitVec3;
+use crate::math::{constants::PI, types::UnitVec3};
 use crate::{color::Color, hittable::HitRecord, materials::Material, ray::Ray};
 
 #[derive(serde::Deserialize)]
@@ -13,11 +13,20 @@ impl Diffuse {
 }
 
 impl Material for Diffuse {
-    fn scatter(&self, _ray: &Ray, record: &HitRecord) -> Option<(Color, Ray)> {
+    fn scatter(&self, ray: &Ray, record: &HitRecord) -> Option<(Color, Ray)> {
         let scatter_direction = UnitVec3::random_on_hemisphere(&mut rand::rng(), record.normal);
-        let scattered_ray = Ray::new(record.hit_point, scatter_direction);
+        let scattered_ray = Ray::with_time(record.hit_point, scatter_direction, ray.time());
         Some((self.albedo, scattered_ray))
     }
+
+    /// Directions are scattered uniformly over the hemisphere.
+    fn evaluate(&self, record: &HitRecord, direction: UnitVec3) -> Option<(Color, f64)> {
+        if record.normal.dot(*direction) <= 0.0 {
+            return Some((Color::BLACK, 0.0));
+        }
+        let pdf = 1.0 / (2.0 * PI);
+        Some((self.albedo * pdf, pdf))
+    }
 }
 
 mod tests {
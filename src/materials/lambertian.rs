@@ -1,4 +1,7 @@
-use crate::math::types::{DVec3Extension, UnitVec3};
+use crate::math::{
+    constants::PI,
+    types::{DVec3Extension, UnitVec3},
+};
 use crate::{color::Color, hittable::HitRecord, materials::Material, ray::Ray};
 
 #[derive(serde::Deserialize)]
@@ -13,16 +16,22 @@ impl Lambertian {
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _ray: &Ray, record: &HitRecord) -> Option<(Color, Ray)> {
+    fn scatter(&self, ray: &Ray, record: &HitRecord) -> Option<(Color, Ray)> {
         let mut scatter_direction = *record.normal + *UnitVec3::random(&mut rand::rng());
 
         if scatter_direction.near_zero() {
             scatter_direction = record.normal.into_inner();
         }
 
-        let scattered_ray = Ray::new(record.hit_point, scatter_direction);
+        let scattered_ray = Ray::with_time(record.hit_point, scatter_direction, ray.time());
         Some((self.albedo, scattered_ray))
     }
+
+    /// Directions are scattered with a cosine weighted density.
+    fn evaluate(&self, record: &HitRecord, direction: UnitVec3) -> Option<(Color, f64)> {
+        let cos_theta = record.normal.dot(*direction).max(0.0);
+        Some((self.albedo * (cos_theta / PI), cos_theta / PI))
+    }
 }
 
 mod tests {
@@ -0,0 +1,119 @@
+use crate::{
+    color::Color,
+    hittable::{FrontFace, HitRecord},
+    materials::Material,
+    ray::Ray,
+};
+
+/// A surface that emits light evenly in every direction and scatters none.
+#[derive(serde::Deserialize)]
+pub struct DiffuseLight {
+    color: Color,
+    /// Scales `color`.
+    #[serde(default = "DiffuseLight::default_strength")]
+    strength: f64,
+    /// Whether only the side the normal faces emits.
+    #[serde(default)]
+    one_sided: bool,
+}
+
+impl DiffuseLight {
+    pub fn new(color: Color) -> Self {
+        DiffuseLight {
+            color,
+            strength: DiffuseLight::default_strength(),
+            one_sided: false,
+        }
+    }
+
+    /// Scales the emitted color.
+    pub fn strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// Emits only from the side the outward normal faces.
+    pub fn one_sided(mut self, one_sided: bool) -> Self {
+        self.one_sided = one_sided;
+        self
+    }
+
+    fn default_strength() -> f64 {
+        1.0
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _ray: &Ray, _record: &HitRecord) -> Option<(Color, Ray)> {
+        None
+    }
+
+    fn emitted(&self, record: &HitRecord) -> Color {
+        match record.front_face {
+            FrontFace::Inside if self.one_sided => Color::BLACK,
+            _ => self.color * self.strength,
+        }
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use crate::materials::{MaterialId, TomlMaterial};
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [[material.light]]
+            id = 1
+            color = [1.0, 0.9, 0.8]
+
+            [[material.light]]
+            id = 2
+            color = [1.0, 1.0, 1.0]
+            strength = 15.0
+            one_sided = true
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            material: MaterialConfig,
+        }
+
+        #[derive(Deserialize)]
+        struct MaterialConfig {
+            light: Vec<TomlMaterial<DiffuseLight>>,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let [bulb, panel] = &config.material.light[..] else {
+            panic!("expected two materials");
+        };
+        assert!(MaterialId(1) == bulb.id);
+        assert!(Color::new(1.0, 0.9, 0.8) == bulb.data.color);
+        assert!(1.0 == bulb.data.strength && !bulb.data.one_sided);
+        assert!(15.0 == panel.data.strength && panel.data.one_sided);
+    }
+
+    #[test]
+    fn emitted() {
+        use super::*;
+        use crate::materials::MaterialId;
+        use crate::math::types::{Point3, UnitVec3, Vec3};
+
+        let normal = UnitVec3::from(Vec3::Y);
+        let from_above = Ray::new(Point3::Y, Vec3::NEG_Y);
+        let from_below = Ray::new(Point3::NEG_Y, Vec3::Y);
+        let above = HitRecord::new(&from_above, 1.0, Point3::ZERO, normal, MaterialId(0));
+        let below = HitRecord::new(&from_below, 1.0, Point3::ZERO, normal, MaterialId(0));
+
+        let light = DiffuseLight::new(Color::new(1.0, 0.5, 0.25)).strength(4.0);
+        assert!(light.scatter(&from_above, &above).is_none());
+        assert!(Color::new(4.0, 2.0, 1.0) == light.emitted(&above));
+        assert!(Color::new(4.0, 2.0, 1.0) == light.emitted(&below));
+
+        let light = light.one_sided(true);
+        assert!(Color::new(4.0, 2.0, 1.0) == light.emitted(&above));
+        assert!(Color::BLACK == light.emitted(&below));
+    }
+}
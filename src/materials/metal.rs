@@ -1,20 +1,40 @@
+use crate::math::types::UnitVec3;
 use crate::{color::Color, hittable::HitRecord, materials::Material, ray::Ray};
 
 #[derive(serde::Deserialize)]
 pub struct Metal {
     albedo: Color,
+    /// The radius, up to one, of the sphere reflections are jittered in.
+    #[serde(default)]
+    fuzz: f64,
 }
 
 impl Metal {
     pub fn new(albedo: Color) -> Self {
-        Metal { albedo }
+        Metal { albedo, fuzz: 0.0 }
+    }
+
+    /// Blurs reflections, from a mirror at zero to satin at one.
+    pub fn fuzz(mut self, fuzz: f64) -> Self {
+        self.fuzz = fuzz;
+        self
     }
 }
 
 impl Material for Metal {
+    /// Rays jittered below the surface are absorbed.
     fn scatter(&self, ray: &Ray, record: &HitRecord) -> Option<(Color, Ray)> {
-        let refelcted_direction = ray.direction().reflect(*record.normal);
-        let scattered_ray = Ray::new(record.hit_point, refelcted_direction);
+        let mut refelcted_direction = ray.direction().reflect(*record.normal);
+
+        if self.fuzz > 0.0 {
+            let jitter = *UnitVec3::random(&mut rand::rng());
+            refelcted_direction += self.fuzz.min(1.0) * jitter;
+            if refelcted_direction.dot(*record.normal) <= 0.0 {
+                return None;
+            }
+        }
+
+        let scattered_ray = Ray::with_time(record.hit_point, refelcted_direction, ray.time());
         Some((self.albedo, scattered_ray))
     }
 }
@@ -45,5 +65,67 @@ mod tests {
         let config: Config = toml::from_str(toml_str).unwrap();
         assert!(MaterialId(1) == config.material.metal.id);
         assert!(Color::new(0.5, 0.5, 0.5) == config.material.metal.data.albedo);
+        assert!(0.0 == config.material.metal.data.fuzz);
+    }
+
+    #[test]
+    fn deserialize_fuzz() {
+        use super::*;
+        use crate::materials::TomlMaterial;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [material.metal]
+            id = 1
+            albedo = [0.5, 0.5, 0.5]
+            fuzz = 0.3
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            material: MaterialConfig,
+        }
+
+        #[derive(Deserialize)]
+        struct MaterialConfig {
+            metal: TomlMaterial<Metal>,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(0.3 == config.material.metal.data.fuzz);
+    }
+
+    #[test]
+    fn fuzzy_reflection() {
+        use super::*;
+        use crate::materials::MaterialId;
+        use crate::math::types::{Point3, Vec3};
+
+        let normal = UnitVec3::from(Vec3::Y);
+        let mirror_direction = Vec3::new(1.0, 1.0, 0.0).normalize();
+
+        let ray = Ray::new(Point3::new(-1.0, 1.0, 0.0), Vec3::new(1.0, -1.0, 0.0));
+        let record = HitRecord::new(&ray, 2f64.sqrt(), Point3::ZERO, normal, MaterialId(0));
+
+        let (_, scattered) = Metal::new(Color::WHITE).scatter(&ray, &record).unwrap();
+        assert!(scattered.direction().abs_diff_eq(mirror_direction, 1e-12));
+
+        // Reflections stay within the fuzz sphere around the mirror direction.
+        let satin = Metal::new(Color::WHITE).fuzz(0.3);
+        let max_angle = 0.3f64.asin();
+        for _ in 0..100 {
+            let (_, scattered) = satin.scatter(&ray, &record).unwrap();
+            let angle = scattered.direction().angle_between(mirror_direction);
+            assert!(angle <= max_angle + 1e-9);
+        }
+
+        // At grazing angles some rays are jittered below the surface and absorbed.
+        let ray = Ray::new(Point3::new(-1.0, 0.01, 0.0), Vec3::new(1.0, -0.01, 0.0));
+        let record = HitRecord::new(&ray, 1.0, Point3::ZERO, normal, MaterialId(0));
+        let brushed = Metal::new(Color::WHITE).fuzz(1.0);
+        let absorbed = (0..100)
+            .filter(|_| brushed.scatter(&ray, &record).is_none())
+            .count();
+        assert!(absorbed > 0);
     }
 }
@@ -1,18 +1,22 @@
 //! Materials.
 
-use crate::{color::Color, hittable::HitRecord, ray::Ray};
+use crate::{color::Color, hittable::HitRecord, math::types::UnitVec3, ray::Ray};
 use std::{
     collections::HashMap,
     sync::atomic::{AtomicU32, Ordering},
 };
 
+mod dielectric;
 mod diffuse;
 mod lambertian;
+mod light;
 mod metal;
 mod normals;
 
+pub use dielectric::{Dielectric, Fresnel};
 pub use diffuse::Diffuse;
 pub use lambertian::Lambertian;
+pub use light::DiffuseLight;
 pub use metal::Metal;
 pub use normals::Normals;
 
@@ -39,6 +43,14 @@ impl MaterialMap {
     pub fn get(&self, id: MaterialId) -> Option<&DynMaterial> {
         self.0.get(&id)
     }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 pub trait Material {
@@ -47,6 +59,15 @@ pub trait Material {
     fn emitted(&self, _record: &HitRecord) -> Color {
         Color::BLACK
     }
+
+    /// Returns the scattering function times the cosine toward `direction`,
+    /// and the density with which [`scatter`](Material::scatter) picks it.
+    ///
+    /// Lights are sampled directly only from materials that return `Some`.
+    /// The default suits specular materials, which no light sample can reach.
+    fn evaluate(&self, _record: &HitRecord, _direction: UnitVec3) -> Option<(Color, f64)> {
+        None
+    }
 }
 
 #[derive(serde::Deserialize)]
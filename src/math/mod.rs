@@ -2,4 +2,5 @@
 
 pub mod constants;
 pub mod primitives;
+pub mod transform;
 pub mod types;
@@ -0,0 +1,171 @@
+use crate::math::types::{Aabb, Interval, Point3};
+use crate::{
+    hittable::{HitRecord, Hittable},
+    materials::MaterialId,
+    math::primitives::{
+        Primitive,
+        revolution::{Frame, SurfaceHit, frustum_side, nearest, solve_quadratic},
+    },
+    ray::Ray,
+};
+
+/// A cylinder closed by hemispheres.
+#[derive(Copy, Clone, serde::Deserialize)]
+pub struct Capsule {
+    /// The center of the bottom hemisphere.
+    base: Point3,
+    /// The center of the top hemisphere.
+    top: Point3,
+    radius: f64,
+    /// The material id of the surface.
+    pub(crate) material_id: MaterialId,
+}
+
+impl Capsule {
+    pub fn new<P, M>(base: P, top: P, radius: f64, material_id: M) -> Self
+    where
+        P: Into<Point3>,
+        M: Into<MaterialId>,
+    {
+        Capsule {
+            base: base.into(),
+            top: top.into(),
+            radius,
+            material_id: material_id.into(),
+        }
+    }
+
+    /// Intersects the hemisphere at the top, or at the base if not `top`.
+    fn hemisphere(
+        &self,
+        frame: &Frame,
+        top: bool,
+        ray: &Ray,
+        ray_t: Interval,
+    ) -> Option<SurfaceHit> {
+        let center = if top { self.top } else { self.base };
+        let r = self.radius;
+
+        let oc = ray.origin() - center;
+        let b = 2.0 * ray.direction().dot(oc);
+        let c = oc.length_squared() - r * r;
+        let (t0, t1) = solve_quadratic(1.0, b, c)?;
+
+        [t0, t1].into_iter().find_map(|t| {
+            let p = ray.at(t);
+            let y = frame.height_of(p);
+            let outside = if top { y >= frame.height } else { y <= 0.0 };
+            (ray_t.surrounds(t) && outside).then(|| SurfaceHit {
+                t,
+                outward_normal: p - center,
+                u: frame.turn(p),
+                v: (y + r) / (frame.height + 2.0 * r),
+            })
+        })
+    }
+}
+
+impl Primitive for Capsule {}
+
+impl Hittable for Capsule {
+    /// `u` is the angle around the axis as a fraction of a turn and `v` runs
+    /// from the bottom to the top of the whole capsule.
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let frame = Frame::new(self.base, self.top);
+        let r = self.radius;
+
+        let side = frustum_side(&frame, r, r, ray, ray_t).map(|mut hit| {
+            hit.v = (hit.v * frame.height + r) / (frame.height + 2.0 * r);
+            hit
+        });
+
+        nearest([
+            side,
+            self.hemisphere(&frame, false, ray, ray_t),
+            self.hemisphere(&frame, true, ray, ray_t),
+        ])
+        .map(|hit| hit.into_record(ray, self.material_id))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = self.radius;
+        Aabb::from_points(self.base - r, self.base + r)
+            .union(Aabb::from_points(self.top - r, self.top + r))
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.capsule]
+            base = [0.0, -0.5, -2.0]
+            top = [0.0, 0.5, -2.0]
+            radius = 0.25
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            capsule: Capsule,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Point3::new(0.0, -0.5, -2.0) == config.primitive.capsule.base);
+        assert!(0.25 == config.primitive.capsule.radius);
+        assert!(MaterialId(1) == config.primitive.capsule.material_id);
+    }
+
+    #[test]
+    fn hit() {
+        use super::*;
+        use crate::hittable::FrontFace;
+        use crate::math::types::Vec3;
+
+        let capsule = Capsule::new(
+            Point3::new(0.0, -1.0, -3.0),
+            Point3::new(0.0, 1.0, -3.0),
+            1.0,
+            MaterialId(0),
+        );
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        // The side.
+        let record = capsule
+            .hit(&Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::NEG_Z), ray_t)
+            .unwrap();
+        assert!((record.t - 2.0).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::Z, 1e-12));
+        assert!((record.v - 0.5).abs() < 1e-12);
+
+        // The tip of the top hemisphere.
+        let record = capsule
+            .hit(&Ray::new(Point3::new(0.0, 5.0, -3.0), Vec3::NEG_Y), ray_t)
+            .unwrap();
+        assert!((record.t - 3.0).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::Y, 1e-12));
+        assert!((record.v - 1.0).abs() < 1e-12);
+
+        // The round end, past where a flat cap would be.
+        let ray = Ray::new(Point3::new(0.0, -1.5, 0.0), Vec3::NEG_Z);
+        let record = capsule.hit(&ray, ray_t).unwrap();
+        assert!((record.t - (3.0 - 0.75f64.sqrt())).abs() < 1e-12);
+
+        // From inside.
+        let ray = Ray::new(Point3::new(0.0, 0.0, -3.0), Vec3::NEG_Y);
+        let record = capsule.hit(&ray, ray_t).unwrap();
+        assert!((record.t - 2.0).abs() < 1e-12);
+        assert!(matches!(record.front_face, FrontFace::Inside));
+
+        let bbox = capsule.bounding_box();
+        assert!(-2.0 == bbox.y.0 && 2.0 == bbox.y.1);
+    }
+}
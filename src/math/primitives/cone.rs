@@ -0,0 +1,129 @@
+use crate::math::types::{Aabb, Interval, Point3};
+use crate::{
+    hittable::{HitRecord, Hittable},
+    materials::MaterialId,
+    math::primitives::{
+        Primitive,
+        revolution::{Frame, cap, frustum_side, nearest},
+    },
+    ray::Ray,
+};
+
+/// A cone closed by a flat base.
+#[derive(Copy, Clone, serde::Deserialize)]
+pub struct Cone {
+    /// The center of the base.
+    base: Point3,
+    /// The tip of the cone.
+    apex: Point3,
+    /// The radius of the base.
+    radius: f64,
+    /// The material id of the surface.
+    pub(crate) material_id: MaterialId,
+}
+
+impl Cone {
+    pub fn new<P, M>(base: P, apex: P, radius: f64, material_id: M) -> Self
+    where
+        P: Into<Point3>,
+        M: Into<MaterialId>,
+    {
+        Cone {
+            base: base.into(),
+            apex: apex.into(),
+            radius,
+            material_id: material_id.into(),
+        }
+    }
+}
+
+impl Primitive for Cone {}
+
+impl Hittable for Cone {
+    /// `u` is the angle around the axis as a fraction of a turn. On the side
+    /// `v` runs from the base to the apex, on the base from the center to the
+    /// rim.
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let frame = Frame::new(self.base, self.apex);
+
+        nearest([
+            frustum_side(&frame, self.radius, 0.0, ray, ray_t),
+            cap(&frame, 0.0, self.radius, false, ray, ray_t),
+        ])
+        .map(|hit| hit.into_record(ray, self.material_id))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let frame = Frame::new(self.base, self.apex);
+        frame
+            .disk_bounds(0.0, self.radius)
+            .union(Aabb::from_points(self.apex, self.apex))
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.cone]
+            base = [0.0, -0.5, -2.0]
+            apex = [0.0, 0.5, -2.0]
+            radius = 0.5
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            cone: Cone,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Point3::new(0.0, 0.5, -2.0) == config.primitive.cone.apex);
+        assert!(0.5 == config.primitive.cone.radius);
+        assert!(MaterialId(1) == config.primitive.cone.material_id);
+    }
+
+    #[test]
+    fn hit() {
+        use super::*;
+        use crate::math::types::Vec3;
+
+        // A 45 degree cone with its apex at the origin.
+        let cone = Cone::new(
+            Point3::new(0.0, -1.0, 0.0),
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            MaterialId(0),
+        );
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        let record = cone
+            .hit(&Ray::new(Point3::new(3.0, -0.5, 0.0), Vec3::NEG_X), ray_t)
+            .unwrap();
+        assert!((record.t - 2.5).abs() < 1e-12);
+        let expected = Vec3::new(1.0, 1.0, 0.0).normalize();
+        assert!(record.normal.abs_diff_eq(expected, 1e-12));
+        assert!((record.v - 0.5).abs() < 1e-12);
+
+        let record = cone
+            .hit(&Ray::new(Point3::new(0.5, -3.0, 0.0), Vec3::Y), ray_t)
+            .unwrap();
+        assert!((record.t - 2.0).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::NEG_Y, 1e-12));
+
+        // Beside the apex, inside the cylinder around the cone.
+        let ray = Ray::new(Point3::new(0.8, -0.1, 3.0), Vec3::NEG_Z);
+        assert!(cone.hit(&ray, ray_t).is_none());
+
+        let bbox = cone.bounding_box();
+        assert!((bbox.y.0 + 1.0).abs() < 0.001 && bbox.y.1.abs() < 0.001);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::math::types::{Aabb, Interval, Point3, UnitVec3, Vec3};
+use crate::{
+    hittable::{HitRecord, Hittable},
+    materials::MaterialId,
+    math::{primitives::Primitive, transform::rotation},
+    ray::Ray,
+};
+use glam::DQuat;
+
+/// A rectangular box, optionally rotated about its center.
+///
+/// Configured as `[[primitive.box]]`.
+#[derive(Copy, Clone, serde::Deserialize)]
+#[serde(from = "TomlCuboid")]
+pub struct Cuboid {
+    center: Point3,
+    /// Half the size along each local axis.
+    half_extent: Vec3,
+    /// The rotation from local to world space.
+    rotation: DQuat,
+    /// The material id of the surface.
+    pub(crate) material_id: MaterialId,
+}
+
+impl Cuboid {
+    /// Constructs an axis-aligned box with opposite corners `a` and `b`.
+    pub fn new<P, M>(a: P, b: P, material_id: M) -> Self
+    where
+        P: Into<Point3>,
+        M: Into<MaterialId>,
+    {
+        let (a, b) = (a.into(), b.into());
+        Cuboid {
+            center: (a + b) / 2.0,
+            half_extent: (b - a).abs() / 2.0,
+            rotation: DQuat::IDENTITY,
+            material_id: material_id.into(),
+        }
+    }
+
+    /// Rotates the box about its center by degrees about the x, then y, then
+    /// z axes.
+    pub fn rotate(mut self, degrees: impl Into<Vec3>) -> Self {
+        self.rotation = rotation(degrees.into());
+        self
+    }
+}
+
+impl Primitive for Cuboid {}
+
+impl Hittable for Cuboid {
+    /// The texture coordinates span each face from zero to one.
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        // Intersect in the box's local frame, where rotation preserves `t`.
+        let inverse = self.rotation.inverse();
+        let origin = inverse * (ray.origin() - self.center);
+        let direction = inverse * *ray.direction();
+
+        let h = self.half_extent;
+        let t0 = (-h - origin) / direction;
+        let t1 = (h - origin) / direction;
+        let t_near = t0.min(t1).max_element();
+        let t_far = t0.max(t1).min_element();
+
+        if t_near > t_far {
+            return None;
+        }
+
+        let t = if ray_t.surrounds(t_near) {
+            t_near
+        } else if ray_t.surrounds(t_far) {
+            t_far
+        } else {
+            return None;
+        };
+
+        // The face is on the axis where the point is furthest out.
+        let local = (origin + t * direction) / h;
+        let axis = local.abs().max_position();
+        let mut normal = Vec3::ZERO;
+        normal[axis] = local[axis].signum();
+
+        let (j, k) = ((axis + 1) % 3, (axis + 2) % 3);
+        let (u, v) = ((local[j] + 1.0) / 2.0, (local[k] + 1.0) / 2.0);
+
+        Some(
+            HitRecord::new(
+                ray,
+                t,
+                ray.at(t),
+                UnitVec3::new_unchecked(self.rotation * normal),
+                self.material_id,
+            )
+            .with_uv(u, v),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // The extent of the rotated box along each world axis.
+        let extent = glam::DMat3::from_quat(self.rotation).abs() * self.half_extent;
+        Aabb::from_points(self.center - extent, self.center + extent)
+    }
+}
+
+/// `[[primitive.box]]` table.
+#[derive(serde::Deserialize)]
+struct TomlCuboid {
+    /// Two opposite corners.
+    corners: [Point3; 2],
+    /// Rotation in degrees about the x, then y, then z axes.
+    #[serde(default)]
+    rotate: Vec3,
+    material_id: MaterialId,
+}
+
+impl From<TomlCuboid> for Cuboid {
+    fn from(value: TomlCuboid) -> Self {
+        let [a, b] = value.corners;
+        Cuboid::new(a, b, value.material_id).rotate(value.rotate)
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.box]
+            corners = [[1.0, 0.0, -3.0], [-1.0, 2.0, -2.0]]
+            rotate = [0.0, 45.0, 0.0]
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            #[serde(rename = "box")]
+            cuboid: Cuboid,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let cuboid = config.primitive.cuboid;
+        assert!(Point3::new(0.0, 1.0, -2.5) == cuboid.center);
+        assert!(Vec3::new(1.0, 1.0, 0.5) == cuboid.half_extent);
+        assert!(MaterialId(1) == cuboid.material_id);
+        assert!((cuboid.rotation.to_axis_angle().1.to_degrees() - 45.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn face_normals() {
+        use super::*;
+        use crate::hittable::FrontFace;
+
+        let cuboid = Cuboid::new(
+            Point3::new(-1.0, -1.0, -3.0),
+            Point3::new(1.0, 1.0, -1.0),
+            MaterialId(0),
+        );
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        let cases = [
+            (Point3::new(0.0, 0.0, 0.0), Vec3::NEG_Z, Vec3::Z, 1.0),
+            (Point3::new(0.0, 5.0, -2.0), Vec3::NEG_Y, Vec3::Y, 4.0),
+            (Point3::new(-4.0, 0.0, -2.0), Vec3::X, Vec3::NEG_X, 3.0),
+        ];
+        for (origin, direction, normal, t) in cases {
+            let record = cuboid.hit(&Ray::new(origin, direction), ray_t).unwrap();
+            assert!((record.t - t).abs() < 1e-12);
+            assert!(record.normal.abs_diff_eq(normal, 1e-12));
+            assert!(matches!(record.front_face, FrontFace::Outside));
+        }
+
+        // From inside, the far face is hit from behind.
+        let ray = Ray::new(Point3::new(0.0, 0.0, -2.0), Vec3::X);
+        let record = cuboid.hit(&ray, ray_t).unwrap();
+        assert!((record.t - 1.0).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::NEG_X, 1e-12));
+        assert!(matches!(record.front_face, FrontFace::Inside));
+
+        let ray = Ray::new(Point3::new(2.0, 2.0, 0.0), Vec3::NEG_Z);
+        assert!(cuboid.hit(&ray, ray_t).is_none());
+    }
+
+    #[test]
+    fn rotated() {
+        use super::*;
+
+        // A unit cube turned 45 degrees about y shows an edge to +z.
+        let cuboid = Cuboid::new(
+            Point3::new(-0.5, -0.5, -0.5),
+            Point3::new(0.5, 0.5, 0.5),
+            MaterialId(0),
+        )
+        .rotate([0.0, 45.0, 0.0]);
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        let record = cuboid
+            .hit(&Ray::new(Point3::new(0.1, 0.0, 5.0), Vec3::NEG_Z), ray_t)
+            .unwrap();
+        assert!((record.t - (5.0 - (0.5f64.sqrt() - 0.1))).abs() < 1e-12);
+        let expected = Vec3::new(1.0, 0.0, 1.0).normalize();
+        assert!(record.normal.abs_diff_eq(expected, 1e-12));
+
+        let bbox = cuboid.bounding_box();
+        assert!((bbox.x.1 - 0.5f64.sqrt()).abs() < 1e-12);
+        assert!((bbox.y.1 - 0.5).abs() < 1e-12);
+    }
+}
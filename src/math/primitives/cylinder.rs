@@ -0,0 +1,140 @@
+use crate::math::types::{Aabb, Interval, Point3};
+use crate::{
+    hittable::{HitRecord, Hittable},
+    materials::MaterialId,
+    math::primitives::{
+        Primitive,
+        revolution::{Frame, cap, frustum_side, nearest},
+    },
+    ray::Ray,
+};
+
+/// A cylinder closed by flat caps.
+#[derive(Copy, Clone, serde::Deserialize)]
+pub struct Cylinder {
+    /// The center of the bottom cap.
+    base: Point3,
+    /// The center of the top cap.
+    top: Point3,
+    radius: f64,
+    /// The material id of the surface.
+    pub(crate) material_id: MaterialId,
+}
+
+impl Cylinder {
+    pub fn new<P, M>(base: P, top: P, radius: f64, material_id: M) -> Self
+    where
+        P: Into<Point3>,
+        M: Into<MaterialId>,
+    {
+        Cylinder {
+            base: base.into(),
+            top: top.into(),
+            radius,
+            material_id: material_id.into(),
+        }
+    }
+}
+
+impl Primitive for Cylinder {}
+
+impl Hittable for Cylinder {
+    /// `u` is the angle around the axis as a fraction of a turn. On the side
+    /// `v` runs from the base to the top, on the caps from the center to the rim.
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let frame = Frame::new(self.base, self.top);
+        let r = self.radius;
+
+        nearest([
+            frustum_side(&frame, r, r, ray, ray_t),
+            cap(&frame, 0.0, r, false, ray, ray_t),
+            cap(&frame, frame.height, r, true, ray, ray_t),
+        ])
+        .map(|hit| hit.into_record(ray, self.material_id))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let frame = Frame::new(self.base, self.top);
+        frame
+            .disk_bounds(0.0, self.radius)
+            .union(frame.disk_bounds(frame.height, self.radius))
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.cylinder]
+            base = [0.0, -0.5, -2.0]
+            top = [0.0, 0.5, -2.0]
+            radius = 0.5
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            cylinder: Cylinder,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Point3::new(0.0, 0.5, -2.0) == config.primitive.cylinder.top);
+        assert!(0.5 == config.primitive.cylinder.radius);
+        assert!(MaterialId(1) == config.primitive.cylinder.material_id);
+    }
+
+    #[test]
+    fn hit() {
+        use super::*;
+        use crate::hittable::FrontFace;
+        use crate::math::types::Vec3;
+
+        let cylinder = Cylinder::new(
+            Point3::new(0.0, -1.0, -3.0),
+            Point3::new(0.0, 1.0, -3.0),
+            1.0,
+            MaterialId(0),
+        );
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        // The side.
+        let record = cylinder
+            .hit(&Ray::new(Point3::new(0.0, 0.5, 0.0), Vec3::NEG_Z), ray_t)
+            .unwrap();
+        assert!((record.t - 2.0).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::Z, 1e-12));
+        assert!((record.v - 0.75).abs() < 1e-12);
+
+        // The top cap.
+        let record = cylinder
+            .hit(&Ray::new(Point3::new(0.5, 5.0, -3.0), Vec3::NEG_Y), ray_t)
+            .unwrap();
+        assert!((record.t - 4.0).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::Y, 1e-12));
+        assert!((record.v - 0.5).abs() < 1e-12);
+
+        // The bottom cap from inside.
+        let record = cylinder
+            .hit(&Ray::new(Point3::new(0.0, 0.0, -3.0), Vec3::NEG_Y), ray_t)
+            .unwrap();
+        assert!((record.t - 1.0).abs() < 1e-12);
+        assert!(matches!(record.front_face, FrontFace::Inside));
+
+        // Past the top.
+        let ray = Ray::new(Point3::new(0.0, 1.5, 0.0), Vec3::NEG_Z);
+        assert!(cylinder.hit(&ray, ray_t).is_none());
+
+        let bbox = cylinder.bounding_box();
+        assert!(-1.0 == bbox.x.0 && 1.0 == bbox.x.1);
+        assert!((bbox.y.0 + 1.0).abs() < 0.001 && (bbox.y.1 - 1.0).abs() < 0.001);
+        assert!(-4.0 == bbox.z.0 && -2.0 == bbox.z.1);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::math::types::{Aabb, Interval, Point3, UnitVec3, Vec3};
+use crate::{
+    hittable::{HitRecord, Hittable},
+    materials::MaterialId,
+    math::{
+        constants::PI,
+        primitives::{Primitive, plane::intersect_plane},
+    },
+    ray::Ray,
+};
+
+/// A flat circle.
+#[derive(Copy, Clone, serde::Deserialize)]
+pub struct Disk {
+    center: Point3,
+    /// The direction the disk faces.
+    normal: Vec3,
+    radius: f64,
+    /// The material id of the surface.
+    pub(crate) material_id: MaterialId,
+}
+
+impl Disk {
+    pub fn new<P, V, M>(center: P, normal: V, radius: f64, material_id: M) -> Self
+    where
+        P: Into<Point3>,
+        V: Into<Vec3>,
+        M: Into<MaterialId>,
+    {
+        Disk {
+            center: center.into(),
+            normal: normal.into(),
+            radius,
+            material_id: material_id.into(),
+        }
+    }
+}
+
+impl Primitive for Disk {}
+
+impl Hittable for Disk {
+    /// The texture coordinates are the angle around the normal as a fraction
+    /// of a turn and the distance from the center as a fraction of the radius.
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let n = self.normal.normalize();
+        let t = intersect_plane(ray, ray_t, self.center, n)?;
+
+        let hit_point = ray.at(t);
+        let planar = hit_point - self.center;
+        let r = planar.length();
+        if r > self.radius {
+            return None;
+        }
+
+        let (tangent, bitangent) = n.any_orthonormal_pair();
+        let phi = planar.dot(bitangent).atan2(planar.dot(tangent));
+        let u = phi.rem_euclid(2.0 * PI) / (2.0 * PI);
+        let v = r / self.radius;
+
+        Some(
+            HitRecord::new(
+                ray,
+                t,
+                hit_point,
+                UnitVec3::new_unchecked(n),
+                self.material_id,
+            )
+            .with_uv(u, v),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // The disk's extent along each axis shrinks as the normal turns toward it.
+        let n = self.normal.normalize();
+        let extent = self.radius * (Vec3::ONE - n * n).max(Vec3::ZERO).map(f64::sqrt);
+        Aabb::from_points(self.center - extent, self.center + extent)
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.disk]
+            center = [0.0, 0.0, -2.0]
+            normal = [0.0, 0.0, 1.0]
+            radius = 0.5
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            disk: Disk,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Vec3::new(0.0, 0.0, -2.0) == config.primitive.disk.center);
+        assert!(0.5 == config.primitive.disk.radius);
+        assert!(MaterialId(1) == config.primitive.disk.material_id);
+    }
+
+    #[test]
+    fn hit() {
+        use super::*;
+
+        let disk = Disk::new(
+            Point3::new(0.0, 0.0, -2.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            1.0,
+            MaterialId(0),
+        );
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        let record = disk
+            .hit(&Ray::new(Point3::new(0.5, 0.0, 0.0), Vec3::NEG_Z), ray_t)
+            .unwrap();
+        assert!((record.t - 2.0).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::Z, 1e-12));
+        assert!((record.v - 0.5).abs() < 1e-12);
+        assert!((0.0..1.0).contains(&record.u));
+
+        let ray = Ray::new(Point3::new(0.8, 0.8, 0.0), Vec3::NEG_Z);
+        assert!(disk.hit(&ray, ray_t).is_none());
+
+        let bbox = disk.bounding_box();
+        assert!(-1.0 == bbox.x.0 && 1.0 == bbox.x.1);
+        assert!(bbox.z.size() < 0.001);
+    }
+}
@@ -0,0 +1,317 @@
+use crate::math::primitives::triangle::{intersect, shade};
+use crate::math::types::{Aabb, Interval, Point3, Vec3};
+use crate::{
+    bvh::{Bvh, BvhConfig},
+    hittable::{DynHittable, HitRecord, Hittable},
+    materials::MaterialId,
+    math::primitives::Primitive,
+    ray::Ray,
+};
+use glam::DAffine3;
+use std::{fmt, sync::Arc};
+
+/// Vertex and index buffers of a triangle mesh.
+///
+/// Normals and texture coordinates, when present, are indexed like positions.
+#[derive(Clone, Default, serde::Deserialize)]
+pub struct MeshData {
+    pub positions: Vec<Point3>,
+    #[serde(default)]
+    pub normals: Option<Vec<Vec3>>,
+    #[serde(default)]
+    pub uvs: Option<Vec<[f64; 2]>>,
+    /// Vertex indices of each triangle in counter-clockwise order.
+    pub indices: Vec<[usize; 3]>,
+}
+
+impl MeshData {
+    /// Checks that every index refers to a vertex and every attribute has
+    /// one entry per vertex.
+    pub fn validate(&self) -> Result<(), MeshError> {
+        let len = self.positions.len();
+
+        if let Some(normals) = &self.normals
+            && normals.len() != len
+        {
+            return Err(MeshError::AttributeCount("normals", normals.len(), len));
+        }
+
+        if let Some(uvs) = &self.uvs
+            && uvs.len() != len
+        {
+            return Err(MeshError::AttributeCount("uvs", uvs.len(), len));
+        }
+
+        if let Some(&index) = self.indices.iter().flatten().find(|&&i| i >= len) {
+            return Err(MeshError::IndexOutOfRange(index, len));
+        }
+
+        Ok(())
+    }
+
+    /// Applies an affine transform to the positions and normals.
+    pub fn transform(&mut self, affine: DAffine3) {
+        for p in &mut self.positions {
+            *p = affine.transform_point3(*p);
+        }
+
+        if let Some(normals) = &mut self.normals {
+            let inverse_transpose = affine.matrix3.inverse().transpose();
+            for n in normals {
+                *n = (inverse_transpose * *n).normalize_or_zero();
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MeshError {
+    /// An index is not less than the number of vertices.
+    IndexOutOfRange(usize, usize),
+    /// An attribute does not have one entry per vertex.
+    AttributeCount(&'static str, usize, usize),
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshError::IndexOutOfRange(i, len) => {
+                write!(f, "Mesh index {i} out of range for {len} vertices")
+            }
+            MeshError::AttributeCount(name, n, len) => {
+                write!(f, "Mesh has {n} {name} for {len} vertices")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MeshError {}
+
+/// A triangle that references the shared buffers of its mesh.
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    index: usize,
+}
+
+impl MeshTriangle {
+    fn vertices(&self) -> [Point3; 3] {
+        self.mesh.indices[self.index].map(|i| self.mesh.positions[i])
+    }
+}
+
+impl Hittable for MeshTriangle {
+    /// The material is filled in by the owning [`TriangleMesh`].
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let [a, b, c] = self.vertices();
+        let (t, b1, b2) = intersect(ray, ray_t, a, b, c)?;
+
+        let indices = self.mesh.indices[self.index];
+        let normals = self.mesh.normals.as_ref().map(|n| indices.map(|i| n[i]));
+        let uvs = self.mesh.uvs.as_ref().map(|uv| indices.map(|i| uv[i]));
+
+        Some(shade(
+            ray,
+            t,
+            [a, b, c],
+            (b1, b2),
+            normals,
+            uvs,
+            MaterialId(0),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let [a, b, c] = self.vertices();
+        Aabb::from_points(a.min(b).min(c), a.max(b).max(c))
+    }
+}
+
+/// An indexed triangle mesh with its own BVH over its triangles.
+#[derive(serde::Deserialize)]
+#[serde(try_from = "TomlMesh")]
+pub struct TriangleMesh {
+    bvh: Bvh,
+    /// The material id of the surface.
+    pub(crate) material_id: MaterialId,
+}
+
+impl TriangleMesh {
+    pub fn new<M>(data: MeshData, material_id: M) -> Result<Self, MeshError>
+    where
+        M: Into<MaterialId>,
+    {
+        data.validate()?;
+
+        let mesh = Arc::new(data);
+        let triangles: Vec<DynHittable> = (0..mesh.indices.len())
+            .map(|index| {
+                Arc::new(MeshTriangle {
+                    mesh: mesh.clone(),
+                    index,
+                }) as DynHittable
+            })
+            .collect();
+
+        Ok(TriangleMesh {
+            bvh: Bvh::new(&triangles, BvhConfig::default()),
+            material_id: material_id.into(),
+        })
+    }
+}
+
+impl Primitive for TriangleMesh {}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let mut record = self.bvh.hit(ray, ray_t)?;
+        record.material = self.material_id;
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+}
+
+/// `[[primitive.mesh]]` table.
+#[derive(serde::Deserialize)]
+struct TomlMesh {
+    #[serde(flatten)]
+    data: MeshData,
+    material_id: MaterialId,
+}
+
+impl TryFrom<TomlMesh> for TriangleMesh {
+    type Error = MeshError;
+
+    fn try_from(value: TomlMesh) -> Result<Self, Self::Error> {
+        TriangleMesh::new(value.data, value.material_id)
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.mesh]
+            positions = [[0.0, 0.0, -1.0], [1.0, 0.0, -1.0], [1.0, 1.0, -1.0], [0.0, 1.0, -1.0]]
+            indices = [[0, 1, 2], [0, 2, 3]]
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            mesh: TriangleMesh,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(MaterialId(1) == config.primitive.mesh.material_id);
+
+        let bbox = config.primitive.mesh.bounding_box();
+        assert!(0.0 == bbox.x.0 && 1.0 == bbox.x.1);
+        assert!(0.0 == bbox.y.0 && 1.0 == bbox.y.1);
+    }
+
+    #[test]
+    fn deserialize_invalid_index() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.mesh]
+            positions = [[0.0, 0.0, -1.0], [1.0, 0.0, -1.0], [1.0, 1.0, -1.0]]
+            indices = [[0, 1, 3]]
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            #[allow(unused)]
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            #[allow(unused)]
+            mesh: TriangleMesh,
+        }
+
+        let err = toml::from_str::<Config>(toml_str).err().unwrap();
+        assert!(
+            err.message()
+                .contains("Mesh index 3 out of range for 3 vertices")
+        );
+    }
+
+    #[test]
+    fn matches_triangles() {
+        use super::*;
+        use crate::math::{primitives::Triangle, types::UnitVec3};
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        let mut rng = StdRng::seed_from_u64(3);
+
+        // A bumpy grid of quads.
+        let n = 10;
+        let mut data = MeshData::default();
+        for j in 0..=n {
+            for i in 0..=n {
+                let (x, z) = (i as f64 - 5.0, j as f64 - 5.0);
+                data.positions
+                    .push(Point3::new(x, rng.random_range(-0.5..0.5), z));
+                data.uvs.get_or_insert_default().push([x, z]);
+            }
+        }
+        for j in 0..n {
+            for i in 0..n {
+                let k = j * (n + 1) + i;
+                data.indices.push([k, k + n + 1, k + 1]);
+                data.indices.push([k + 1, k + n + 1, k + n + 2]);
+            }
+        }
+
+        let triangles: Vec<Triangle> = data
+            .indices
+            .iter()
+            .map(|&[a, b, c]| {
+                let p = &data.positions;
+                let uv = data.uvs.as_ref().unwrap();
+                Triangle::new(p[a], p[b], p[c], MaterialId(7)).with_uvs([uv[a], uv[b], uv[c]])
+            })
+            .collect();
+        let mesh = TriangleMesh::new(data, MaterialId(7)).unwrap();
+
+        let ray_t = Interval(0.001, f64::INFINITY);
+        for _ in 0..500 {
+            let origin = Point3::new(
+                rng.random_range(-6.0..6.0),
+                rng.random_range(-3.0..3.0),
+                rng.random_range(-6.0..6.0),
+            );
+            let ray = Ray::new(origin, UnitVec3::random(&mut rng));
+
+            let expected = triangles
+                .iter()
+                .filter_map(|t| t.hit(&ray, ray_t))
+                .min_by(|a, b| a.t.total_cmp(&b.t));
+
+            match (expected, mesh.hit(&ray, ray_t)) {
+                (None, None) => {}
+                (Some(a), Some(b)) => {
+                    assert!(a.t == b.t);
+                    assert!(a.u == b.u && a.v == b.v);
+                    assert!(MaterialId(7) == b.material);
+                }
+                _ => panic!("mesh and triangles disagree"),
+            }
+        }
+    }
+}
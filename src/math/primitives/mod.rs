@@ -1,8 +1,40 @@
 //! Primitives module.
 
+use crate::math::transform::Transform;
+
+mod capsule;
+mod cone;
+mod cuboid;
+mod cylinder;
+mod disk;
+mod mesh;
+mod plane;
+mod quad;
+mod revolution;
 mod sphere;
+mod torus;
+mod triangle;
 
+pub use capsule::Capsule;
+pub use cone::Cone;
+pub use cuboid::Cuboid;
+pub use cylinder::Cylinder;
+pub use disk::Disk;
+pub use mesh::{MeshData, MeshError, TriangleMesh};
+pub use plane::Plane;
+pub use quad::Quad;
 pub use sphere::Sphere;
+pub use torus::Torus;
+pub use triangle::Triangle;
 
 /// A marker trait for geometric primitives.
 pub trait Primitive {}
+
+/// A `[[primitive.*]]` table with an optional `transform`.
+#[derive(serde::Deserialize)]
+pub struct TomlPrimitive<P: Primitive> {
+    #[serde(default)]
+    pub transform: Option<Transform>,
+    #[serde(flatten)]
+    pub data: P,
+}
@@ -0,0 +1,176 @@
+use crate::math::types::{Aabb, Interval, Point3, UnitVec3, Vec3};
+use crate::{
+    hittable::{HitRecord, Hittable},
+    materials::MaterialId,
+    math::primitives::Primitive,
+    ray::Ray,
+};
+
+/// An infinite plane.
+#[derive(Copy, Clone, serde::Deserialize)]
+pub struct Plane {
+    /// Any point on the plane.
+    point: Point3,
+    /// The direction the plane faces.
+    normal: Vec3,
+    /// The material id of the surface.
+    pub(crate) material_id: MaterialId,
+}
+
+impl Plane {
+    pub fn new<P, V, M>(point: P, normal: V, material_id: M) -> Self
+    where
+        P: Into<Point3>,
+        V: Into<Vec3>,
+        M: Into<MaterialId>,
+    {
+        Plane {
+            point: point.into(),
+            normal: normal.into(),
+            material_id: material_id.into(),
+        }
+    }
+}
+
+impl Primitive for Plane {}
+
+impl Hittable for Plane {
+    /// The texture coordinates are the distances from `point` along two
+    /// directions in the plane, so textures repeat every unit.
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let n = self.normal.normalize();
+        let t = intersect_plane(ray, ray_t, self.point, n)?;
+
+        let hit_point = ray.at(t);
+        let planar = hit_point - self.point;
+        let (tangent, bitangent) = n.any_orthonormal_pair();
+
+        Some(
+            HitRecord::new(
+                ray,
+                t,
+                hit_point,
+                UnitVec3::new_unchecked(n),
+                self.material_id,
+            )
+            .with_uv(planar.dot(tangent), planar.dot(bitangent)),
+        )
+    }
+
+    /// Unbounded, except along the normal of an axis-aligned plane.
+    fn bounding_box(&self) -> Aabb {
+        let n = self.normal;
+        let axis = |normal: f64, others: [f64; 2], p: f64| {
+            if others == [0.0, 0.0] && normal != 0.0 {
+                Interval(p, p)
+            } else {
+                Interval::UNIVERSE
+            }
+        };
+
+        Aabb::new(
+            axis(n.x, [n.y, n.z], self.point.x),
+            axis(n.y, [n.x, n.z], self.point.y),
+            axis(n.z, [n.x, n.y], self.point.z),
+        )
+    }
+}
+
+/// Returns the ray parameter where the ray crosses the plane through `point`
+/// with normal `n`.
+pub(crate) fn intersect_plane(ray: &Ray, ray_t: Interval, point: Point3, n: Vec3) -> Option<f64> {
+    let denom = ray.direction().dot(n);
+    if denom.abs() < 1e-12 {
+        return None;
+    }
+
+    let t = n.dot(point - ray.origin()) / denom;
+    ray_t.surrounds(t).then_some(t)
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.plane]
+            point = [0.0, -0.5, 0.0]
+            normal = [0.0, 1.0, 0.0]
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            plane: Plane,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Vec3::new(0.0, -0.5, 0.0) == config.primitive.plane.point);
+        assert!(Vec3::Y == config.primitive.plane.normal);
+        assert!(MaterialId(1) == config.primitive.plane.material_id);
+    }
+
+    #[test]
+    fn hit() {
+        use super::*;
+        use crate::hittable::FrontFace;
+
+        let plane = Plane::new(Point3::new(0.0, -1.0, 0.0), Vec3::Y, MaterialId(0));
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        let ray = Ray::new(Point3::new(100.0, 0.0, -100.0), Vec3::new(0.0, -1.0, -1.0));
+        let record = plane.hit(&ray, ray_t).unwrap();
+        assert!((record.t - 2f64.sqrt()).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::Y, 1e-12));
+        assert!(matches!(record.front_face, FrontFace::Outside));
+
+        let ray = Ray::new(Point3::ZERO, Vec3::X);
+        assert!(plane.hit(&ray, ray_t).is_none());
+
+        let bbox = plane.bounding_box();
+        assert!(!bbox.is_finite());
+        assert!(bbox.y.contains(-1.0) && bbox.y.size() < 0.001);
+    }
+
+    #[test]
+    fn in_bvh() {
+        use super::*;
+        use crate::{
+            bvh::{Bvh, BvhConfig},
+            hittable::HittableList,
+            math::primitives::Sphere,
+        };
+
+        let mut list = HittableList::default();
+        list.add(Plane::new(
+            Point3::new(0.0, -1.0, 0.0),
+            Vec3::Y,
+            MaterialId(0),
+        ));
+        list.add(Plane::new(
+            Point3::ZERO,
+            Vec3::new(1.0, 1.0, 0.0),
+            MaterialId(1),
+        ));
+        list.add(Sphere::new(Point3::new(0.0, 0.0, -3.0), 0.5, MaterialId(2)));
+        let bvh = Bvh::new(list.objects(), BvhConfig::default());
+
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        let record = bvh.hit(&Ray::new(Point3::ZERO, Vec3::NEG_Z), ray_t);
+        assert!(Some(MaterialId(2)) == record.map(|r| r.material));
+
+        let ray = Ray::new(Point3::new(-2.0, 0.0, 0.0), Vec3::new(0.0, -1.0, -1.0));
+        assert!(Some(MaterialId(0)) == bvh.hit(&ray, ray_t).map(|r| r.material));
+
+        let ray = Ray::new(Point3::new(-2.0, 0.0, 0.0), Vec3::X);
+        assert!(Some(MaterialId(1)) == bvh.hit(&ray, ray_t).map(|r| r.material));
+    }
+}
@@ -0,0 +1,138 @@
+use crate::math::types::{Aabb, Interval, Point3, UnitVec3, Vec3};
+use crate::{
+    hittable::{HitRecord, Hittable},
+    materials::MaterialId,
+    math::primitives::{Primitive, plane::intersect_plane},
+    ray::Ray,
+};
+
+/// A parallelogram spanned by two edges from a corner.
+#[derive(Copy, Clone, serde::Deserialize)]
+pub struct Quad {
+    /// The corner where both edges start.
+    origin: Point3,
+    /// The edge along which `u` increases.
+    u: Vec3,
+    /// The edge along which `v` increases.
+    v: Vec3,
+    /// The material id of the surface.
+    pub(crate) material_id: MaterialId,
+}
+
+impl Quad {
+    /// Constructs a quad with corners `origin`, `origin + u`, `origin + u + v`
+    /// and `origin + v`, facing `u × v`.
+    pub fn new<P, V, M>(origin: P, u: V, v: V, material_id: M) -> Self
+    where
+        P: Into<Point3>,
+        V: Into<Vec3>,
+        M: Into<MaterialId>,
+    {
+        Quad {
+            origin: origin.into(),
+            u: u.into(),
+            v: v.into(),
+            material_id: material_id.into(),
+        }
+    }
+}
+
+impl Primitive for Quad {}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let n = self.u.cross(self.v);
+        let t = intersect_plane(ray, ray_t, self.origin, n)?;
+
+        // Express the hit point in the basis of the edges.
+        let hit_point = ray.at(t);
+        let planar = hit_point - self.origin;
+        let w = n / n.length_squared();
+        let alpha = w.dot(planar.cross(self.v));
+        let beta = w.dot(self.u.cross(planar));
+
+        let unit = 0.0..=1.0;
+        if !unit.contains(&alpha) || !unit.contains(&beta) {
+            return None;
+        }
+
+        Some(
+            HitRecord::new(
+                ray,
+                t,
+                hit_point,
+                UnitVec3::new_normalize(n),
+                self.material_id,
+            )
+            .with_uv(alpha, beta),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let (a, b) = (self.origin, self.origin + self.u + self.v);
+        let (c, d) = (self.origin + self.u, self.origin + self.v);
+        Aabb::from_points(a, b).union(Aabb::from_points(c, d))
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.quad]
+            origin = [-1.0, -1.0, -2.0]
+            u = [2.0, 0.0, 0.0]
+            v = [0.0, 2.0, 0.0]
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            quad: Quad,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Vec3::new(-1.0, -1.0, -2.0) == config.primitive.quad.origin);
+        assert!(Vec3::new(0.0, 2.0, 0.0) == config.primitive.quad.v);
+        assert!(MaterialId(1) == config.primitive.quad.material_id);
+    }
+
+    #[test]
+    fn hit() {
+        use super::*;
+        use crate::hittable::FrontFace;
+
+        // A parallelogram, so the uvs are not just the planar coordinates.
+        let quad = Quad::new(
+            Point3::new(-1.0, -1.0, -2.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(1.0, 2.0, 0.0),
+            MaterialId(0),
+        );
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        let record = quad
+            .hit(&Ray::new(Point3::new(0.5, 0.0, 0.0), Vec3::NEG_Z), ray_t)
+            .unwrap();
+        assert!((record.t - 2.0).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::Z, 1e-12));
+        assert!(matches!(record.front_face, FrontFace::Outside));
+        assert!((record.u - 0.5).abs() < 1e-12 && (record.v - 0.5).abs() < 1e-12);
+
+        // Inside the bounding square but outside the parallelogram.
+        let ray = Ray::new(Point3::new(-0.9, 0.9, 0.0), Vec3::NEG_Z);
+        assert!(quad.hit(&ray, ray_t).is_none());
+
+        let bbox = quad.bounding_box();
+        assert!(-1.0 == bbox.x.0 && 2.0 == bbox.x.1);
+        assert!(-1.0 == bbox.y.0 && 1.0 == bbox.y.1);
+    }
+}
@@ -0,0 +1,190 @@
+//! Intersection helpers for surfaces of revolution.
+
+use crate::math::types::{Aabb, Interval, Point3, UnitVec3, Vec3};
+use crate::{hittable::HitRecord, materials::MaterialId, math::constants::PI, ray::Ray};
+
+/// A frame along the axis of a surface of revolution.
+pub(crate) struct Frame {
+    pub origin: Point3,
+    /// The unit axis.
+    pub axis: Vec3,
+    /// The distance along the axis to the far end.
+    pub height: f64,
+    tangent: Vec3,
+    bitangent: Vec3,
+}
+
+impl Frame {
+    /// Returns the frame from `base` to `top`, pointing up the y axis if they
+    /// coincide.
+    pub fn new(base: Point3, top: Point3) -> Self {
+        let height = base.distance(top);
+        let mut frame = Frame::along(base, top - base);
+        frame.height = height;
+        frame
+    }
+
+    /// Returns a frame of zero height at `origin`.
+    pub fn along(origin: Point3, axis: Vec3) -> Self {
+        let axis = axis.try_normalize().unwrap_or(Vec3::Y);
+        let (tangent, bitangent) = axis.any_orthonormal_pair();
+        Frame {
+            origin,
+            axis,
+            height: 0.0,
+            tangent,
+            bitangent,
+        }
+    }
+
+    /// Returns the distance of `p` along the axis.
+    pub fn height_of(&self, p: Point3) -> f64 {
+        (p - self.origin).dot(self.axis)
+    }
+
+    /// Returns the component of `v` perpendicular to the axis.
+    pub fn radial(&self, v: Vec3) -> Vec3 {
+        v - v.dot(self.axis) * self.axis
+    }
+
+    /// Returns the angle of `p` around the axis as a fraction of a turn.
+    pub fn turn(&self, p: Point3) -> f64 {
+        let r = p - self.origin;
+        let phi = r.dot(self.bitangent).atan2(r.dot(self.tangent));
+        phi.rem_euclid(2.0 * PI) / (2.0 * PI)
+    }
+
+    /// Returns the bounds of a disk of radius `r` at height `y`.
+    pub fn disk_bounds(&self, y: f64, r: f64) -> Aabb {
+        let center = self.origin + y * self.axis;
+        let extent = r
+            * (Vec3::ONE - self.axis * self.axis)
+                .max(Vec3::ZERO)
+                .map(f64::sqrt);
+        Aabb::from_points(center - extent, center + extent)
+    }
+}
+
+/// An intersection before it is turned into a [`HitRecord`].
+pub(crate) struct SurfaceHit {
+    pub t: f64,
+    pub outward_normal: Vec3,
+    pub u: f64,
+    pub v: f64,
+}
+
+impl SurfaceHit {
+    pub fn into_record(self, ray: &Ray, material_id: MaterialId) -> HitRecord {
+        HitRecord::new(
+            ray,
+            self.t,
+            ray.at(self.t),
+            UnitVec3::new_normalize(self.outward_normal),
+            material_id,
+        )
+        .with_uv(self.u, self.v)
+    }
+}
+
+/// Returns the closest of the hits.
+pub(crate) fn nearest(hits: impl IntoIterator<Item = Option<SurfaceHit>>) -> Option<SurfaceHit> {
+    hits.into_iter()
+        .flatten()
+        .min_by(|a, b| a.t.total_cmp(&b.t))
+}
+
+/// Returns the real roots of `a t^2 + b t + c` in ascending order.
+pub(crate) fn solve_quadratic(a: f64, b: f64, c: f64) -> Option<(f64, f64)> {
+    if a.abs() < 1e-12 {
+        if b.abs() < 1e-12 {
+            return None;
+        }
+        let t = -c / b;
+        return Some((t, t));
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    // Avoid cancellation between `b` and the root.
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    let (t0, t1) = if q == 0.0 { (0.0, 0.0) } else { (q / a, c / q) };
+    Some((t0.min(t1), t0.max(t1)))
+}
+
+/// Intersects the side of a cone frustum with radius `r0` at the base and
+/// `r1` at the top.
+///
+/// `v` runs from zero at the base to one at the top.
+pub(crate) fn frustum_side(
+    frame: &Frame,
+    r0: f64,
+    r1: f64,
+    ray: &Ray,
+    ray_t: Interval,
+) -> Option<SurfaceHit> {
+    let h = frame.height;
+    let slope = if h > 0.0 { (r1 - r0) / h } else { 0.0 };
+
+    let oc = ray.origin() - frame.origin;
+    let d = *ray.direction();
+    let (o_perp, d_perp) = (frame.radial(oc), frame.radial(d));
+    let (oy, dy) = (oc.dot(frame.axis), d.dot(frame.axis));
+
+    // |radial(p)| = r0 + slope * y
+    let r = r0 + slope * oy;
+    let a = d_perp.length_squared() - slope * slope * dy * dy;
+    let b = 2.0 * (o_perp.dot(d_perp) - slope * r * dy);
+    let c = o_perp.length_squared() - r * r;
+
+    let (t0, t1) = solve_quadratic(a, b, c)?;
+    [t0, t1].into_iter().find_map(|t| {
+        let y = oy + t * dy;
+        if !ray_t.surrounds(t) || !(0.0..=h).contains(&y) {
+            return None;
+        }
+
+        let p = ray.at(t);
+        let radial = frame.radial(p - frame.origin).normalize_or_zero();
+        Some(SurfaceHit {
+            t,
+            outward_normal: radial - slope * frame.axis,
+            u: frame.turn(p),
+            v: if h > 0.0 { y / h } else { 0.0 },
+        })
+    })
+}
+
+/// Intersects a flat cap of radius `r` at height `y`, facing up the axis if
+/// `up`.
+///
+/// `v` runs from zero at the center to one at the rim.
+pub(crate) fn cap(
+    frame: &Frame,
+    y: f64,
+    r: f64,
+    up: bool,
+    ray: &Ray,
+    ray_t: Interval,
+) -> Option<SurfaceHit> {
+    let dy = ray.direction().dot(frame.axis);
+    if dy.abs() < 1e-12 {
+        return None;
+    }
+
+    let t = (y - frame.height_of(ray.origin())) / dy;
+    if !ray_t.surrounds(t) {
+        return None;
+    }
+
+    let p = ray.at(t);
+    let distance = frame.radial(p - frame.origin).length();
+    (distance <= r).then(|| SurfaceHit {
+        t,
+        outward_normal: if up { frame.axis } else { -frame.axis },
+        u: frame.turn(p),
+        v: distance / r,
+    })
+}
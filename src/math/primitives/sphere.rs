@@ -1,4 +1,4 @@
-use crate::math::types::{Interval, Point3, UnitVec3};
+use crate::math::types::{Aabb, Interval, Point3, UnitVec3};
 use crate::{
     hittable::{HitRecord, Hittable},
     materials::MaterialId,
@@ -8,8 +8,10 @@ use crate::{
 
 #[derive(Copy, Clone, serde::Deserialize)]
 pub struct Sphere {
-    /// The center of the sphere.
+    /// The center of the sphere at time zero.
     center: Point3,
+    /// The center of the sphere at time one, if it is moving.
+    center1: Option<Point3>,
     /// The radius of the sphere.
     radius: f64,
     /// The material id of the surface.
@@ -24,10 +26,37 @@ impl Sphere {
     {
         Sphere {
             center: center.into(),
+            center1: None,
             radius,
             material_id: material_id.into(),
         }
     }
+
+    /// Constructs a sphere that moves linearly from `center0` at time zero
+    /// to `center1` at time one.
+    pub fn new_moving<P, M>(center0: P, center1: P, radius: f64, material_id: M) -> Self
+    where
+        P: Into<Point3>,
+        M: Into<MaterialId>,
+    {
+        Sphere {
+            center: center0.into(),
+            center1: Some(center1.into()),
+            radius,
+            material_id: material_id.into(),
+        }
+    }
+
+    /// Returns the center of the sphere at the given time.
+    ///
+    /// The sphere rests at `center0` before time zero and at `center1` after
+    /// time one, so it never leaves its bounding box.
+    pub fn center(&self, time: f64) -> Point3 {
+        match self.center1 {
+            Some(center1) => self.center.lerp(center1, time.clamp(0.0, 1.0)),
+            None => self.center,
+        }
+    }
 }
 
 impl Primitive for Sphere {}
@@ -36,7 +65,8 @@ impl Hittable for Sphere {
     fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
         let (r_origin, r_direction) = (ray.origin(), ray.direction());
 
-        let oc = self.center - r_origin;
+        let center = self.center(ray.time());
+        let oc = center - r_origin;
         let a = r_direction.length_squared();
         let h = r_direction.dot(oc); // let b = -2h
         let c = oc.length_squared() - self.radius.powi(2);
@@ -59,7 +89,7 @@ impl Hittable for Sphere {
         }
 
         let hit_point = ray.at(t);
-        let outward_normal = UnitVec3::new_unchecked((hit_point - self.center) / self.radius);
+        let outward_normal = UnitVec3::new_unchecked((hit_point - center) / self.radius);
 
         Some(HitRecord::new(
             ray,
@@ -69,6 +99,15 @@ impl Hittable for Sphere {
             self.material_id,
         ))
     }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = self.radius;
+        let bbox = Aabb::from_points(self.center - r, self.center + r);
+        match self.center1 {
+            Some(center1) => bbox.union(Aabb::from_points(center1 - r, center1 + r)),
+            None => bbox,
+        }
+    }
 }
 
 mod tests {
@@ -99,5 +138,87 @@ mod tests {
         assert!(Vec3::new(0.0, 0.0, -1.0) == config.primitive.sphere.center);
         assert!(0.5 == config.primitive.sphere.radius);
         assert!(MaterialId(1) == config.primitive.sphere.material_id);
+        assert!(config.primitive.sphere.center1.is_none());
+    }
+
+    #[test]
+    fn deserialize_moving() {
+        use super::*;
+        use crate::math::types::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.sphere]
+            center = [0.0, 0.0, -1.0]
+            center1 = [0.0, 0.5, -1.0]
+            radius = 0.5
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            sphere: Sphere,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Some(Vec3::new(0.0, 0.5, -1.0)) == config.primitive.sphere.center1);
+        assert!(Vec3::new(0.0, 0.25, -1.0) == config.primitive.sphere.center(0.5));
+    }
+
+    #[test]
+    fn hit_moving() {
+        use super::*;
+        use crate::math::types::*;
+
+        let sphere = Sphere::new_moving(
+            Point3::new(0.0, 0.0, -2.0),
+            Point3::new(2.0, 0.0, -2.0),
+            0.5,
+            MaterialId(0),
+        );
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        let ray = Ray::with_time(Point3::ZERO, Vec3::NEG_Z, 0.0);
+        assert!(sphere.hit(&ray, ray_t).is_some());
+
+        let ray = Ray::with_time(Point3::ZERO, Vec3::NEG_Z, 1.0);
+        assert!(sphere.hit(&ray, ray_t).is_none());
+
+        let ray = Ray::with_time(Point3::new(2.0, 0.0, 0.0), Vec3::NEG_Z, 1.0);
+        let record = sphere.hit(&ray, ray_t).unwrap();
+        assert!((record.t - 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn hit_moving_outside_unit_time() {
+        use super::*;
+        use crate::{math::types::*, world::World};
+
+        let mut world = World::default();
+        world.add_object(Sphere::new_moving(
+            Point3::new(0.0, 0.0, -2.0),
+            Point3::new(2.0, 0.0, -2.0),
+            0.5,
+            MaterialId(0),
+        ));
+        world.add_object(Sphere::new(
+            Point3::new(-4.0, 0.0, -2.0),
+            0.5,
+            MaterialId(0),
+        ));
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        // Shutter times past one see the sphere where it stopped.
+        let ray = Ray::with_time(Point3::new(2.0, 0.0, 0.0), Vec3::NEG_Z, 5.0);
+        let record = world.hittable().hit(&ray, ray_t).unwrap();
+        assert!((record.t - 1.5).abs() < 1e-12);
+
+        let ray = Ray::with_time(Point3::ZERO, Vec3::NEG_Z, -3.0);
+        assert!(world.hittable().hit(&ray, ray_t).is_some());
     }
 }
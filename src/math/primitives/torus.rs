@@ -0,0 +1,292 @@
+use crate::math::types::{Aabb, Interval, Point3, Vec3};
+use crate::{
+    hittable::{HitRecord, Hittable},
+    materials::MaterialId,
+    math::{
+        constants::PI,
+        primitives::{
+            Primitive,
+            revolution::{Frame, SurfaceHit},
+        },
+    },
+    ray::Ray,
+};
+
+/// A ring swept by a circle around an axis.
+#[derive(Copy, Clone, serde::Deserialize)]
+pub struct Torus {
+    center: Point3,
+    /// The axis the ring turns around.
+    axis: Vec3,
+    /// The distance from the center to the middle of the tube.
+    major_radius: f64,
+    /// The radius of the tube.
+    minor_radius: f64,
+    /// The material id of the surface.
+    pub(crate) material_id: MaterialId,
+}
+
+impl Torus {
+    pub fn new<P, V, M>(
+        center: P,
+        axis: V,
+        major_radius: f64,
+        minor_radius: f64,
+        material_id: M,
+    ) -> Self
+    where
+        P: Into<Point3>,
+        V: Into<Vec3>,
+        M: Into<MaterialId>,
+    {
+        Torus {
+            center: center.into(),
+            axis: axis.into(),
+            major_radius,
+            minor_radius,
+            material_id: material_id.into(),
+        }
+    }
+}
+
+impl Primitive for Torus {}
+
+impl Hittable for Torus {
+    /// `u` is the angle around the axis and `v` the angle around the tube,
+    /// both as fractions of a turn.
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let frame = Frame::along(self.center, self.axis);
+        let (big_r, r) = (self.major_radius, self.minor_radius);
+        let bound = big_r + r;
+
+        let d = *ray.direction();
+        let oc = ray.origin() - self.center;
+
+        // Start the ray just before the bounding sphere to keep the quartic
+        // well conditioned for distant rays. The new origin lies `bound`
+        // before the closest approach, so a ray that misses the sphere has
+        // |o|^2 = distance^2 + bound^2 > 2 bound^2.
+        let shift = -oc.dot(d) - bound;
+        let o = oc + shift * d;
+        if o.length_squared() > 2.0 * bound * bound {
+            return None;
+        }
+
+        let n = o.dot(d);
+        let k = o.length_squared() + big_r * big_r - r * r;
+        let (o_perp, d_perp) = (frame.radial(o), frame.radial(d));
+        let four_r2 = 4.0 * big_r * big_r;
+
+        // (|p|^2 + R^2 - r^2)^2 = 4 R^2 |radial(p)|^2, lowest degree first.
+        let coefficients = [
+            k * k - four_r2 * o_perp.length_squared(),
+            4.0 * n * k - 2.0 * four_r2 * o_perp.dot(d),
+            4.0 * n * n + 2.0 * k - four_r2 * d_perp.length_squared(),
+            4.0 * n,
+            1.0,
+        ];
+
+        let t = real_roots(&coefficients, 0.0, 2.0 * bound)
+            .into_iter()
+            .map(|s| shift + s)
+            .find(|&t| ray_t.surrounds(t))?;
+
+        let p = ray.at(t);
+        let radial = frame.radial(p - self.center);
+        let ring = self.center + big_r * radial.normalize_or_zero();
+        let tube = p - ring;
+
+        let hit = SurfaceHit {
+            t,
+            outward_normal: tube,
+            u: frame.turn(p),
+            v: {
+                let phi = frame.height_of(p).atan2(radial.length() - big_r);
+                phi.rem_euclid(2.0 * PI) / (2.0 * PI)
+            },
+        };
+        Some(hit.into_record(ray, self.material_id))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let frame = Frame::along(self.center, self.axis);
+        let r = self.minor_radius;
+        let ring = frame.disk_bounds(0.0, self.major_radius);
+        Aabb::new(
+            ring.x.expand(2.0 * r),
+            ring.y.expand(2.0 * r),
+            ring.z.expand(2.0 * r),
+        )
+    }
+}
+
+/// Returns the real roots in `[lo, hi]` of the polynomial with coefficients
+/// `c`, lowest degree first, in ascending order.
+///
+/// The polynomial is monotonic between the roots of its derivative, so each
+/// of those intervals holds at most one root, found by bisection.
+fn real_roots(c: &[f64], lo: f64, hi: f64) -> Vec<f64> {
+    if c.len() <= 2 {
+        return match c {
+            [c0, c1] if *c1 != 0.0 => Some(-c0 / c1),
+            _ => None,
+        }
+        .filter(|x| (lo..=hi).contains(x))
+        .into_iter()
+        .collect();
+    }
+
+    let derivative: Vec<f64> = c[1..]
+        .iter()
+        .enumerate()
+        .map(|(i, ci)| (i + 1) as f64 * ci)
+        .collect();
+
+    let mut bounds = vec![lo];
+    bounds.extend(real_roots(&derivative, lo, hi));
+    bounds.push(hi);
+
+    bounds
+        .windows(2)
+        .filter_map(|w| bisect(c, w[0], w[1]))
+        .collect()
+}
+
+/// Finds a root of a polynomial that is monotonic in `[lo, hi]`.
+fn bisect(c: &[f64], mut lo: f64, mut hi: f64) -> Option<f64> {
+    let eval = |x: f64| c.iter().rev().fold(0.0, |acc, ci| acc * x + ci);
+
+    let f_lo = eval(lo);
+    if f_lo.signum() == eval(hi).signum() {
+        return None;
+    }
+
+    for _ in 0..100 {
+        let mid = (lo + hi) / 2.0;
+        if hi - lo <= 1e-12 * (1.0 + mid.abs()) {
+            break;
+        }
+        if eval(mid).signum() == f_lo.signum() {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    Some((lo + hi) / 2.0)
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.torus]
+            center = [0.0, 0.0, -2.0]
+            axis = [0.0, 1.0, 0.0]
+            major_radius = 0.5
+            minor_radius = 0.125
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            torus: Torus,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Vec3::Y == config.primitive.torus.axis);
+        assert!(0.5 == config.primitive.torus.major_radius);
+        assert!(0.125 == config.primitive.torus.minor_radius);
+        assert!(MaterialId(1) == config.primitive.torus.material_id);
+    }
+
+    #[test]
+    fn roots() {
+        use super::*;
+
+        // (x - 1)(x - 2)(x + 3)(x - 4)
+        let c = [-24.0, 34.0, -7.0, -4.0, 1.0];
+        let roots = real_roots(&c, -10.0, 10.0);
+        assert!(4 == roots.len());
+        for (root, expected) in roots.iter().zip([-3.0, 1.0, 2.0, 4.0]) {
+            assert!((root - expected).abs() < 1e-9);
+        }
+
+        assert!(2 == real_roots(&c, 0.0, 3.0).len());
+    }
+
+    #[test]
+    fn hit() {
+        use super::*;
+        use crate::hittable::FrontFace;
+
+        let torus = Torus::new(
+            Point3::new(0.0, 0.0, -10.0),
+            Vec3::Z,
+            2.0,
+            0.5,
+            MaterialId(0),
+        );
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        // Down the axis through the hole.
+        let ray = Ray::new(Point3::ZERO, Vec3::NEG_Z);
+        assert!(torus.hit(&ray, ray_t).is_none());
+
+        // Through the tube.
+        let ray = Ray::new(Point3::new(2.0, 0.0, 0.0), Vec3::NEG_Z);
+        let record = torus.hit(&ray, ray_t).unwrap();
+        assert!((record.t - 9.5).abs() < 1e-9);
+        assert!(record.normal.abs_diff_eq(Vec3::Z, 1e-9));
+
+        // Across the plane of the ring, hitting the outside of the tube.
+        let ray = Ray::new(Point3::new(-100.0, 0.0, -10.0), Vec3::X);
+        let record = torus.hit(&ray, ray_t).unwrap();
+        assert!((record.t - 97.5).abs() < 1e-9);
+        assert!(record.normal.abs_diff_eq(Vec3::NEG_X, 1e-9));
+        assert!(matches!(record.front_face, FrontFace::Outside));
+
+        // From inside the tube.
+        let ray = Ray::new(Point3::new(2.0, 0.0, -10.0), Vec3::X);
+        let record = torus.hit(&ray, ray_t).unwrap();
+        assert!((record.t - 0.5).abs() < 1e-9);
+        assert!(matches!(record.front_face, FrontFace::Inside));
+
+        let bbox = torus.bounding_box();
+        assert!((bbox.x.1 - 2.5).abs() < 1e-12);
+        assert!((bbox.z.0 + 10.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn near_miss() {
+        use super::*;
+
+        let torus = Torus::new(Point3::ZERO, Vec3::Z, 2.0, 0.5, MaterialId(0));
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        // Just outside and just inside the outer edge of the tube, which
+        // touches the bounding sphere.
+        let ray = Ray::new(Point3::new(-100.0, 2.501, 0.0), Vec3::X);
+        assert!(torus.hit(&ray, ray_t).is_none());
+
+        let ray = Ray::new(Point3::new(-100.0, 2.499, 0.0), Vec3::X);
+        let record = torus.hit(&ray, ray_t).unwrap();
+        assert!(record.normal.y > 0.9);
+
+        // Diagonal rays that pass the bounding sphere on either side.
+        let d = Vec3::new(1.0, 1.0, 1.0).normalize();
+        let offset = Vec3::new(1.0, -1.0, 0.0).normalize();
+        let ray = Ray::new(-100.0 * d + 2.501 * offset, d);
+        assert!(torus.hit(&ray, ray_t).is_none());
+        let ray = Ray::new(-100.0 * d + 2.0 * offset, d);
+        assert!(torus.hit(&ray, ray_t).is_some());
+    }
+}
@@ -0,0 +1,300 @@
+use crate::math::types::{Aabb, Interval, Point3, UnitVec3, Vec3};
+use crate::{
+    hittable::{HitRecord, Hittable},
+    materials::MaterialId,
+    math::primitives::Primitive,
+    ray::Ray,
+};
+
+#[derive(Copy, Clone, serde::Deserialize)]
+pub struct Triangle {
+    /// The corners of the triangle in counter-clockwise order.
+    vertices: [Point3; 3],
+    /// Optional per-vertex shading normals.
+    normals: Option<[Vec3; 3]>,
+    /// Optional per-vertex texture coordinates.
+    uvs: Option<[[f64; 2]; 3]>,
+    /// The material id of the surface.
+    pub(crate) material_id: MaterialId,
+}
+
+impl Triangle {
+    pub fn new<P, M>(a: P, b: P, c: P, material_id: M) -> Self
+    where
+        P: Into<Point3>,
+        M: Into<MaterialId>,
+    {
+        Triangle {
+            vertices: [a.into(), b.into(), c.into()],
+            normals: None,
+            uvs: None,
+            material_id: material_id.into(),
+        }
+    }
+
+    /// Sets per-vertex shading normals.
+    pub fn with_normals(mut self, normals: [Vec3; 3]) -> Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    /// Sets per-vertex texture coordinates.
+    pub fn with_uvs(mut self, uvs: [[f64; 2]; 3]) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+}
+
+impl Primitive for Triangle {}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let [a, b, c] = self.vertices;
+        let (t, b1, b2) = intersect(ray, ray_t, a, b, c)?;
+
+        Some(shade(
+            ray,
+            t,
+            [a, b, c],
+            (b1, b2),
+            self.normals,
+            self.uvs,
+            self.material_id,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let [a, b, c] = self.vertices;
+        Aabb::from_points(a.min(b).min(c), a.max(b).max(c))
+    }
+}
+
+/// Möller–Trumbore ray-triangle intersection.
+///
+/// Returns the ray parameter and the barycentric coordinates of `b` and `c`.
+pub(crate) fn intersect(
+    ray: &Ray,
+    ray_t: Interval,
+    a: Point3,
+    b: Point3,
+    c: Point3,
+) -> Option<(f64, f64, f64)> {
+    const EPSILON: f64 = 1e-12;
+
+    let edge1 = b - a;
+    let edge2 = c - a;
+
+    // The determinant scales with the edges and direction, so the tolerance
+    // for a ray parallel to the plane does too.
+    let pvec = ray.direction().cross(edge2);
+    let det = edge1.dot(pvec);
+    let scale = edge1.length_squared() * edge2.length_squared() * ray.direction().length_squared();
+    if det * det <= EPSILON * EPSILON * scale {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+
+    let tvec = ray.origin() - a;
+    let b1 = tvec.dot(pvec) * inv_det;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let qvec = tvec.cross(edge1);
+    let b2 = ray.direction().dot(qvec) * inv_det;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(qvec) * inv_det;
+    ray_t.surrounds(t).then_some((t, b1, b2))
+}
+
+/// Builds a hit record from barycentric coordinates, interpolating the
+/// optional vertex normals and texture coordinates.
+///
+/// Without texture coordinates the barycentrics of `b` and `c` are used.
+pub(crate) fn shade(
+    ray: &Ray,
+    t: f64,
+    [a, b, c]: [Point3; 3],
+    (b1, b2): (f64, f64),
+    normals: Option<[Vec3; 3]>,
+    uvs: Option<[[f64; 2]; 3]>,
+    material_id: MaterialId,
+) -> HitRecord {
+    let b0 = 1.0 - b1 - b2;
+    let geometric = (b - a).cross(c - a);
+
+    let outward_normal = match normals {
+        Some([n0, n1, n2]) => {
+            let n = b0 * n0 + b1 * n1 + b2 * n2;
+            if n.length_squared() < 1e-12 {
+                // Zero or cancelling vertex normals give no direction to shade with.
+                geometric
+            } else if n.dot(geometric) < 0.0 {
+                // Keep the shading normal on the geometric side of the surface.
+                -n
+            } else {
+                n
+            }
+        }
+        None => geometric,
+    };
+
+    let (u, v) = match uvs {
+        Some([uv0, uv1, uv2]) => (
+            b0 * uv0[0] + b1 * uv1[0] + b2 * uv2[0],
+            b0 * uv0[1] + b1 * uv1[1] + b2 * uv2[1],
+        ),
+        None => (b1, b2),
+    };
+
+    HitRecord::new(
+        ray,
+        t,
+        ray.at(t),
+        UnitVec3::new_normalize(outward_normal),
+        material_id,
+    )
+    .with_uv(u, v)
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [primitive.triangle]
+            vertices = [[0.0, 0.0, -1.0], [1.0, 0.0, -1.0], [0.0, 1.0, -1.0]]
+            uvs = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]
+            material_id = 1
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            triangle: Triangle,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(Vec3::new(1.0, 0.0, -1.0) == config.primitive.triangle.vertices[1]);
+        assert!(config.primitive.triangle.normals.is_none());
+        assert!(config.primitive.triangle.uvs.is_some());
+        assert!(MaterialId(1) == config.primitive.triangle.material_id);
+    }
+
+    #[test]
+    fn hit() {
+        use super::*;
+        use crate::hittable::FrontFace;
+
+        let triangle = Triangle::new(
+            Point3::new(-1.0, -1.0, -2.0),
+            Point3::new(1.0, -1.0, -2.0),
+            Point3::new(-1.0, 1.0, -2.0),
+            MaterialId(0),
+        );
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        let record = triangle
+            .hit(&Ray::new(Point3::ZERO, Vec3::NEG_Z), ray_t)
+            .unwrap();
+        assert!((record.t - 2.0).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::Z, 1e-12));
+        assert!(matches!(record.front_face, FrontFace::Outside));
+        assert!((record.u - 0.5).abs() < 1e-12 && (record.v - 0.5).abs() < 1e-12);
+
+        // Outside the hypotenuse.
+        let ray = Ray::new(Point3::new(0.5, 0.5, 0.0), Vec3::NEG_Z);
+        assert!(triangle.hit(&ray, ray_t).is_none());
+
+        // Parallel to the plane.
+        assert!(
+            triangle
+                .hit(&Ray::new(Point3::ZERO, Vec3::X), ray_t)
+                .is_none()
+        );
+
+        // From behind.
+        let ray = Ray::new(Point3::new(-0.5, -0.5, -4.0), Vec3::Z);
+        let record = triangle.hit(&ray, ray_t).unwrap();
+        assert!(matches!(record.front_face, FrontFace::Inside));
+        assert!(record.normal.abs_diff_eq(Vec3::NEG_Z, 1e-12));
+    }
+
+    #[test]
+    fn interpolates_normals_and_uvs() {
+        use super::*;
+
+        let triangle = Triangle::new(
+            Point3::new(0.0, 0.0, -1.0),
+            Point3::new(1.0, 0.0, -1.0),
+            Point3::new(0.0, 1.0, -1.0),
+            MaterialId(0),
+        )
+        .with_normals([Vec3::Z, Vec3::new(1.0, 0.0, 1.0), Vec3::Z])
+        .with_uvs([[0.0, 0.0], [2.0, 0.0], [0.0, 4.0]]);
+
+        let ray = Ray::new(Point3::new(0.5, 0.25, 0.0), Vec3::NEG_Z);
+        let record = triangle.hit(&ray, Interval(0.001, f64::INFINITY)).unwrap();
+
+        let expected = Vec3::new(0.5, 0.0, 1.0).normalize();
+        assert!(record.normal.abs_diff_eq(expected, 1e-12));
+        assert!((record.u - 1.0).abs() < 1e-12);
+        assert!((record.v - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn zero_normals_fall_back_to_geometric() {
+        use super::*;
+
+        let triangle = Triangle::new(
+            Point3::new(0.0, 0.0, -1.0),
+            Point3::new(1.0, 0.0, -1.0),
+            Point3::new(0.0, 1.0, -1.0),
+            MaterialId(0),
+        );
+        // Barycentrics of (0.25, 0.5, 0.25).
+        let ray = Ray::new(Point3::new(0.5, 0.25, 0.0), Vec3::NEG_Z);
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        // Zero normals, and opposite normals that cancel at the hit point.
+        for normals in [[Vec3::ZERO; 3], [Vec3::Z, Vec3::NEG_Z, Vec3::Z]] {
+            let record = triangle.with_normals(normals).hit(&ray, ray_t).unwrap();
+            assert!(record.normal.abs_diff_eq(Vec3::Z, 1e-12));
+        }
+    }
+
+    #[test]
+    fn hit_tiny() {
+        use super::*;
+
+        let triangle = Triangle::new(
+            Point3::new(-1e-7, -1e-7, -2.0),
+            Point3::new(1e-7, -1e-7, -2.0),
+            Point3::new(-1e-7, 1e-7, -2.0),
+            MaterialId(0),
+        );
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        let ray = Ray::new(Point3::new(-5e-8, -5e-8, 0.0), Vec3::NEG_Z);
+        let record = triangle.hit(&ray, ray_t).unwrap();
+        assert!((record.t - 2.0).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::Z, 1e-12));
+
+        let ray = Ray::new(Point3::new(5e-8, 5e-8, 0.0), Vec3::NEG_Z);
+        assert!(triangle.hit(&ray, ray_t).is_none());
+        assert!(
+            triangle
+                .hit(&Ray::new(Point3::ZERO, Vec3::X), ray_t)
+                .is_none()
+        );
+    }
+}
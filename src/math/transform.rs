@@ -0,0 +1,291 @@
+//! Affine transforms and instancing of hittable objects.
+
+use crate::math::types::{Aabb, Interval, Point3, UnitVec3, Vec3};
+use crate::{
+    hittable::{HitRecord, Hittable},
+    ray::Ray,
+};
+use glam::{DAffine3, DMat3, DQuat};
+
+/// A scale, then a rotation, then a translation.
+#[derive(Copy, Clone, PartialEq, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Transform {
+    translate: Vec3,
+    /// Rotations in degrees about the x, then y, then z axes.
+    rotate: Vec3,
+    scale: Vec3,
+}
+
+impl Transform {
+    pub fn new() -> Self {
+        Transform {
+            translate: Vec3::ZERO,
+            rotate: Vec3::ZERO,
+            scale: Vec3::ONE,
+        }
+    }
+
+    pub fn translate(mut self, translate: impl Into<Vec3>) -> Self {
+        self.translate = translate.into();
+        self
+    }
+
+    /// Sets the rotations in degrees about the x, then y, then z axes.
+    pub fn rotate(mut self, degrees: impl Into<Vec3>) -> Self {
+        self.rotate = degrees.into();
+        self
+    }
+
+    pub fn scale(mut self, scale: impl Into<Vec3>) -> Self {
+        self.scale = scale.into();
+        self
+    }
+
+    /// Returns whether the transform leaves every point in place.
+    pub fn is_identity(&self) -> bool {
+        *self == Transform::new()
+    }
+
+    pub fn to_affine(&self) -> DAffine3 {
+        DAffine3::from_scale_rotation_translation(self.scale, rotation(self.rotate), self.translate)
+    }
+}
+
+/// Returns the rotation by degrees about the x, then y, then z axes.
+pub fn rotation(degrees: Vec3) -> DQuat {
+    let [x, y, z] = degrees.to_array().map(f64::to_radians);
+    DQuat::from_rotation_z(z) * DQuat::from_rotation_y(y) * DQuat::from_rotation_x(x)
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::new()
+    }
+}
+
+impl From<Transform> for DAffine3 {
+    fn from(transform: Transform) -> Self {
+        transform.to_affine()
+    }
+}
+
+/// An object placed in the world by an affine transform.
+///
+/// Rays are moved into object space and hits back out, so one object wrapped
+/// in an [`Arc`](std::sync::Arc) can be placed many times.
+pub struct Transformed<H: Hittable> {
+    object: H,
+    /// Object to world space.
+    transform: DAffine3,
+    /// World to object space.
+    inverse: DAffine3,
+    /// Maps object space normals to world space.
+    normal_matrix: DMat3,
+    bbox: Aabb,
+}
+
+impl<H: Hittable> Transformed<H> {
+    pub fn new(object: H, transform: impl Into<DAffine3>) -> Self {
+        let transform = transform.into();
+        let inverse = transform.inverse();
+        let bbox = transform_bounds(object.bounding_box(), transform);
+
+        Transformed {
+            object,
+            transform,
+            inverse,
+            normal_matrix: inverse.matrix3.transpose(),
+            bbox,
+        }
+    }
+
+    pub fn object(&self) -> &H {
+        &self.object
+    }
+
+    /// Returns the object to world space transform.
+    pub fn transform(&self) -> DAffine3 {
+        self.transform
+    }
+}
+
+impl<H: Hittable> Hittable for Transformed<H> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let origin = self.inverse.transform_point3(ray.origin());
+        let direction = self.inverse.transform_vector3(*ray.direction());
+
+        // Object space rays have unit directions, so distances scale by the
+        // length of the transformed direction.
+        let scale = direction.length();
+        let local_ray = Ray::with_time(origin, direction, ray.time());
+        let local_t = Interval(ray_t.0 * scale, ray_t.1 * scale);
+
+        let mut record = self.object.hit(&local_ray, local_t)?;
+        record.t /= scale;
+        record.hit_point = ray.at(record.t);
+        // The normal already faces against the ray, which the transform preserves.
+        record.normal = UnitVec3::new_normalize(self.normal_matrix * *record.normal);
+        Some(record)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+/// Returns the box enclosing the transformed corners of `bbox`.
+fn transform_bounds(bbox: Aabb, transform: DAffine3) -> Aabb {
+    if !bbox.is_finite() {
+        return Aabb::UNIVERSE;
+    }
+
+    let (lo, hi) = (
+        Point3::new(bbox.x.0, bbox.y.0, bbox.z.0),
+        Point3::new(bbox.x.1, bbox.y.1, bbox.z.1),
+    );
+
+    let corners = (0..8).map(|i| {
+        let mask = glam::BVec3::new(i & 1 != 0, i & 2 != 0, i & 4 != 0);
+        transform.transform_point3(Point3::select(mask, hi, lo))
+    });
+
+    let (min, max) = corners.fold(
+        (
+            Point3::splat(f64::INFINITY),
+            Point3::splat(f64::NEG_INFINITY),
+        ),
+        |(min, max), p| (min.min(p), max.max(p)),
+    );
+    Aabb::from_points(min, max)
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            transform = { translate = [1.0, 2.0, 3.0], rotate = [0.0, 90.0, 0.0] }
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            transform: Transform,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let expected = Transform::new()
+            .translate([1.0, 2.0, 3.0])
+            .rotate([0.0, 90.0, 0.0]);
+        assert!(expected == config.transform);
+        assert!(Vec3::ONE == config.transform.scale);
+    }
+
+    #[test]
+    fn order() {
+        use super::*;
+        use crate::math::types::Point3;
+
+        let affine = Transform::new()
+            .translate([0.0, 0.0, -5.0])
+            .rotate([90.0, 90.0, 0.0])
+            .scale([2.0, 1.0, 1.0])
+            .to_affine();
+
+        // Scaled to (2, 0, 0), about x stays put, about y to (0, 0, -2).
+        let p = affine.transform_point3(Point3::new(1.0, 0.0, 0.0));
+        assert!((p - Point3::new(0.0, 0.0, -7.0)).length() < 1e-12);
+
+        assert!(Transform::default().is_identity());
+    }
+
+    #[test]
+    fn deserialize_primitive() {
+        use super::*;
+        use crate::math::primitives::{Cuboid, Sphere, TomlPrimitive};
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [[primitive.sphere]]
+            center = [0.0, 0.0, 0.0]
+            radius = 1
+            transform = { translate = [0.0, 0.0, -3.0], scale = [2.0, 2.0, 2.0] }
+            material_id = 1
+
+            [[primitive.box]]
+            corners = [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0]]
+            material_id = 2
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            primitive: Primitive,
+        }
+
+        #[derive(Deserialize)]
+        struct Primitive {
+            sphere: Vec<TomlPrimitive<Sphere>>,
+            #[serde(rename = "box")]
+            cuboid: Vec<TomlPrimitive<Cuboid>>,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let sphere = &config.primitive.sphere[0];
+        let expected = Transform::new()
+            .translate([0.0, 0.0, -3.0])
+            .scale([2.0, 2.0, 2.0]);
+        assert!(Some(expected) == sphere.transform);
+
+        let sphere = Transformed::new(sphere.data, sphere.transform.unwrap());
+        let bbox = sphere.bounding_box();
+        assert!((bbox.z.0 + 5.0).abs() < 1e-12 && (bbox.z.1 + 1.0).abs() < 1e-12);
+
+        assert!(config.primitive.cuboid[0].transform.is_none());
+    }
+
+    #[test]
+    fn transformed_hit() {
+        use super::*;
+        use crate::{hittable::FrontFace, materials::MaterialId, math::primitives::Sphere};
+
+        // A unit sphere stretched into an ellipsoid and moved down the z axis.
+        let sphere = Sphere::new(Point3::ZERO, 1.0, MaterialId(0));
+        let transform = Transform::new()
+            .translate([0.0, 0.0, -10.0])
+            .scale([2.0, 1.0, 0.5]);
+        let ellipsoid = Transformed::new(sphere, transform);
+        let ray_t = Interval(0.001, f64::INFINITY);
+
+        let record = ellipsoid
+            .hit(&Ray::new(Point3::ZERO, Vec3::NEG_Z), ray_t)
+            .unwrap();
+        assert!((record.t - 9.5).abs() < 1e-12);
+        assert!((record.hit_point - Point3::new(0.0, 0.0, -9.5)).length() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::Z, 1e-12));
+        assert!(matches!(record.front_face, FrontFace::Outside));
+
+        // The tip of the long axis, missed by the untransformed sphere.
+        let record = ellipsoid
+            .hit(&Ray::new(Point3::new(5.0, 0.0, -10.0), Vec3::NEG_X), ray_t)
+            .unwrap();
+        assert!((record.t - 3.0).abs() < 1e-12);
+        assert!(record.normal.abs_diff_eq(Vec3::X, 1e-12));
+
+        // A sloped normal is not just the transformed object normal.
+        let p = Point3::new(2.0, 0.0, 0.5) * 0.5f64.sqrt();
+        let ray = Ray::new(Point3::new(p.x, 0.0, 5.0), Vec3::NEG_Z);
+        let record = ellipsoid.hit(&ray, ray_t).unwrap();
+        let expected = Vec3::new(p.x / 4.0, 0.0, p.z / 0.25).normalize();
+        assert!(record.normal.abs_diff_eq(expected, 1e-9));
+
+        // The ray interval is respected in world units.
+        let ray = Ray::new(Point3::ZERO, Vec3::NEG_Z);
+        assert!(ellipsoid.hit(&ray, Interval(0.001, 9.0)).is_none());
+
+        let bbox = ellipsoid.bounding_box();
+        assert!((bbox.x.0 + 2.0).abs() < 1e-9 && (bbox.x.1 - 2.0).abs() < 1e-9);
+        assert!((bbox.z.0 + 10.5).abs() < 1e-9 && (bbox.z.1 + 9.5).abs() < 1e-9);
+    }
+}
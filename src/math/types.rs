@@ -1,6 +1,6 @@
 //! This module defines types.
 
-use crate::math::constants::INFINITY;
+use crate::{math::constants::INFINITY, ray::Ray};
 use rand::Rng;
 use std::ops::{Deref, Neg};
 
@@ -82,6 +82,20 @@ impl Neg for UnitVec3 {
     }
 }
 
+/// Generates a random [`Vec3`] in the unit disk on the xy-plane.
+pub fn random_in_unit_disk(rng: &mut impl Rng) -> Vec3 {
+    loop {
+        let p = Vec3::new(
+            rng.random_range(-1.0..1.0),
+            rng.random_range(-1.0..1.0),
+            0.0,
+        );
+        if p.length_squared() < 1.0 {
+            return p;
+        }
+    }
+}
+
 /// An interval defines a range over it's parameters.
 #[derive(Clone, Copy, Debug)]
 pub struct Interval(pub f64, pub f64);
@@ -116,6 +130,17 @@ impl Interval {
     pub fn clamp(&self, x: f64) -> f64 {
         x.clamp(self.0, self.1)
     }
+
+    /// Returns the interval padded by `delta / 2` on each side.
+    pub fn expand(&self, delta: f64) -> Interval {
+        let padding = delta / 2.0;
+        Interval(self.0 - padding, self.1 + padding)
+    }
+
+    /// Returns the smallest interval enclosing both intervals.
+    pub fn union(&self, other: Interval) -> Interval {
+        Interval(self.0.min(other.0), self.1.max(other.1))
+    }
 }
 
 impl Default for Interval {
@@ -130,3 +155,176 @@ impl From<(f64, f64)> for Interval {
         Interval(min, max)
     }
 }
+
+/// An axis-aligned bounding box.
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+impl Aabb {
+    /// A box that contains nothing.
+    pub const EMPTY: Aabb = Aabb {
+        x: Interval::EMPTY,
+        y: Interval::EMPTY,
+        z: Interval::EMPTY,
+    };
+
+    /// A box that contains everything.
+    pub const UNIVERSE: Aabb = Aabb {
+        x: Interval::UNIVERSE,
+        y: Interval::UNIVERSE,
+        z: Interval::UNIVERSE,
+    };
+
+    /// Smallest extent of any axis, so flat boxes can still be hit.
+    const MIN_EXTENT: f64 = 0.0001;
+
+    /// Returns a new box from an interval on each axis.
+    pub fn new(x: Interval, y: Interval, z: Interval) -> Self {
+        Aabb { x, y, z }.padded()
+    }
+
+    /// Returns the box with opposite corners `a` and `b`.
+    pub fn from_points(a: Point3, b: Point3) -> Self {
+        Aabb::new(
+            Interval(a.x.min(b.x), a.x.max(b.x)),
+            Interval(a.y.min(b.y), a.y.max(b.y)),
+            Interval(a.z.min(b.z), a.z.max(b.z)),
+        )
+    }
+
+    /// Returns the smallest box enclosing both boxes.
+    pub fn union(&self, other: Aabb) -> Aabb {
+        Aabb {
+            x: self.x.union(other.x),
+            y: self.y.union(other.y),
+            z: self.z.union(other.z),
+        }
+    }
+
+    /// Returns the interval along `axis`, where 0, 1, 2 are x, y, z.
+    pub fn axis_interval(&self, axis: usize) -> Interval {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            _ => self.z,
+        }
+    }
+
+    /// Returns the index of the longest axis.
+    pub fn longest_axis(&self) -> usize {
+        let (x, y, z) = (self.x.size(), self.y.size(), self.z.size());
+        if x > y {
+            if x > z { 0 } else { 2 }
+        } else if y > z {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Returns the center of the box.
+    pub fn centroid(&self) -> Point3 {
+        Point3::new(
+            (self.x.0 + self.x.1) / 2.0,
+            (self.y.0 + self.y.1) / 2.0,
+            (self.z.0 + self.z.1) / 2.0,
+        )
+    }
+
+    /// Returns the surface area of the box, or zero if it is empty.
+    pub fn surface_area(&self) -> f64 {
+        let (x, y, z) = (self.x.size(), self.y.size(), self.z.size());
+        if x < 0.0 || y < 0.0 || z < 0.0 {
+            return 0.0;
+        }
+        2.0 * (x * y + y * z + z * x)
+    }
+
+    /// Returns `true` if every bound is finite.
+    pub fn is_finite(&self) -> bool {
+        [self.x, self.y, self.z]
+            .iter()
+            .all(|i| i.0.is_finite() && i.1.is_finite())
+    }
+
+    /// Returns `true` if the ray passes through the box within `ray_t`.
+    pub fn hit(&self, ray: &Ray, mut ray_t: Interval) -> bool {
+        let (origin, direction) = (ray.origin(), ray.direction());
+
+        for axis in 0..3 {
+            let ax = self.axis_interval(axis);
+            let adinv = 1.0 / direction[axis];
+
+            let t0 = (ax.0 - origin[axis]) * adinv;
+            let t1 = (ax.1 - origin[axis]) * adinv;
+
+            ray_t.0 = ray_t.0.max(t0.min(t1));
+            ray_t.1 = ray_t.1.min(t0.max(t1));
+
+            if ray_t.1 <= ray_t.0 {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Pads any axis thinner than [`Aabb::MIN_EXTENT`].
+    fn padded(self) -> Aabb {
+        let pad = |i: Interval| {
+            if i.size() < Aabb::MIN_EXTENT {
+                i.expand(Aabb::MIN_EXTENT)
+            } else {
+                i
+            }
+        };
+
+        Aabb {
+            x: pad(self.x),
+            y: pad(self.y),
+            z: pad(self.z),
+        }
+    }
+}
+
+impl Default for Aabb {
+    /// Returns an [`Aabb::EMPTY`] box.
+    fn default() -> Aabb {
+        Aabb::EMPTY
+    }
+}
+
+mod tests {
+    #[test]
+    fn aabb_hit() {
+        use super::*;
+
+        let aabb = Aabb::from_points(Point3::new(-1.0, -1.0, -3.0), Point3::new(1.0, 1.0, -2.0));
+        let ray_t = Interval(0.001, INFINITY);
+
+        assert!(aabb.hit(&Ray::new(Point3::ZERO, Vec3::NEG_Z), ray_t));
+        assert!(!aabb.hit(&Ray::new(Point3::ZERO, Vec3::Z), ray_t));
+        assert!(!aabb.hit(&Ray::new(Point3::ZERO, Vec3::NEG_Z), Interval(0.001, 1.0)));
+        assert!(!aabb.hit(&Ray::new(Point3::new(2.0, 0.0, 0.0), Vec3::NEG_Z), ray_t));
+    }
+
+    #[test]
+    fn aabb_union_and_padding() {
+        use super::*;
+
+        let a = Aabb::from_points(Point3::ZERO, Point3::new(1.0, 0.0, 1.0));
+        let b = Aabb::from_points(Point3::new(-1.0, 2.0, 0.0), Point3::new(0.0, 3.0, 0.5));
+        let c = a.union(b);
+
+        assert!(a.y.size() > 0.0);
+        assert!(-1.0 == c.x.0 && 1.0 == c.x.1);
+        assert!(1 == c.longest_axis());
+        assert!(0.0 == Aabb::EMPTY.surface_area());
+        assert!(22.0 == Aabb::from_points(Point3::ZERO, Point3::new(1.0, 2.0, 3.0)).surface_area());
+        assert!(Point3::new(0.0, 1.5, 0.5).abs_diff_eq(c.centroid(), 1e-4));
+    }
+}
@@ -6,14 +6,21 @@ use crate::math::types::{Point3, UnitVec3};
 pub struct Ray {
     origin: Point3,
     direction: UnitVec3,
+    time: f64,
 }
 
 impl Ray {
-    /// Constructs a new ray with an origin and direction.
+    /// Constructs a new ray with an origin and direction at time zero.
     pub fn new(origin: impl Into<Point3>, direction: impl Into<UnitVec3>) -> Self {
+        Ray::with_time(origin, direction, 0.0)
+    }
+
+    /// Constructs a new ray with an origin and direction at the given time.
+    pub fn with_time(origin: impl Into<Point3>, direction: impl Into<UnitVec3>, time: f64) -> Self {
         Ray {
             origin: origin.into(),
             direction: direction.into(),
+            time,
         }
     }
 
@@ -27,6 +34,11 @@ impl Ray {
         self.direction
     }
 
+    /// Returns the time at which the ray was cast.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
     /// Returns a point along the ray.
     pub fn at(&self, t: f64) -> Point3 {
         self.origin + t * self.direction.into_inner()
@@ -10,6 +10,8 @@
 //! position = [0.0, 0.0, 0.0]
 //! look_at = [0.0, 0.0, -1.0]
 //! up = [0.0, 1.0, 0.0]
+//! vfov = 90.0
+//! defocus_angle = 0.0
 //! max_depth = 50
 //!
 //! ## Choose a sampler.
@@ -18,19 +20,127 @@
 //! nx = 20
 //! ny = 20
 //!
+//! ## Optionally choose how linear radiance is mapped to 8 and 16 bit outputs.
+//! [output]
+//! exposure = 0.0
+//! tone_map = "aces"
+//! transfer = "srgb"
+//!
+//! ## Optionally trade BVH build time for render speed: "fast", "medium" or "high".
+//! [bvh]
+//! quality = "medium"
+//! max_leaf_size = 4
+//!
+//! ## Optionally replace the default "sky" with a "constant" color, a
+//! ## "gradient" from `start` to `end` along `direction`, a "physical" sky,
+//! ## an "environment" map, or "none" so only lights illuminate the scene.
+//! [background]
+//! type = "gradient"
+//! start = [0.0, 0.0, 0.0]
+//! end = [0.1, 0.1, 0.3]
+//! direction = [0.0, 1.0, 0.0]
+//!
+//! ## An environment map is an equirectangular `.hdr` or `.pfm` image with a
+//! ## path relative to the scene file, turned by `rotation` degrees about y.
+//! # [background]
+//! # type = "environment"
+//! # path = "studio.hdr"
+//! # rotation = 90.0
+//! # intensity = 1.0
+//!
+//! ## A physical sky places the sun by degrees above the horizon and from -z
+//! ## toward +x. Turbidity runs from about 2 for clear air to 10 for haze, and
+//! ## `sun_size` is the angular diameter of the sun in degrees.
+//! # [background]
+//! # type = "physical"
+//! # elevation = 30.0
+//! # azimuth = 45.0
+//! # turbidity = 3.0
+//! # sun_size = 0.53
+//!
 //! ## Provide an array of primitives that each reference a material.
 //! [[primitive.sphere]]
-//! center = [0.0, -100.5, -1.0]
-//! radius = 100.0
+//! center = [0.0, 0.0, -1.0]
+//! radius = 0.5
+//! material_id = 1
+//!
+//! ## Any primitive can be scaled, then rotated in degrees about x, y and z,
+//! ## then translated.
+//! [[primitive.sphere]]
+//! center = [0.0, 0.0, 0.0]
+//! radius = 0.5
+//! transform = { translate = [1.0, 0.0, -1.0], rotate = [0.0, 0.0, 45.0], scale = [1.0, 0.5, 1.0] }
+//! material_id = 1
+//!
+//! ## Quads span two edges from a corner, disks and planes face their normal.
+//! [[primitive.plane]]
+//! point = [0.0, -0.5, 0.0]
+//! normal = [0.0, 1.0, 0.0]
+//! material_id = 1
+//!
+//! ## Boxes span two opposite corners and may be rotated about their center.
+//! [[primitive.box]]
+//! corners = [[-0.5, -0.5, -2.5], [0.5, 0.5, -1.5]]
+//! rotate = [0.0, 30.0, 0.0]
+//! material_id = 1
+//!
+//! ## Cylinders and capsules run from `base` to `top`, cones from `base` to `apex`.
+//! [[primitive.cylinder]]
+//! base = [1.0, -0.5, -2.0]
+//! top = [1.0, 0.5, -2.0]
+//! radius = 0.25
+//! material_id = 1
+//!
+//! ## Tori ring around `axis`.
+//! [[primitive.torus]]
+//! center = [-1.0, 0.0, -2.0]
+//! axis = [0.0, 1.0, 0.0]
+//! major_radius = 0.5
+//! minor_radius = 0.125
+//! material_id = 1
+//!
+//! ## Load meshes from OBJ files relative to the scene file. Faces without an
+//! ## MTL material use `material_id`.
+//! [[primitive.obj]]
+//! path = "teapot.obj"
+//! transform = { translate = [0.0, 0.0, -3.0], rotate = [0.0, 45.0, 0.0], scale = [0.5, 0.5, 0.5] }
+//! material_id = 1
+//!
+//! ## PLY (ASCII or binary little-endian) and binary STL files are loaded the
+//! ## same way with `[[primitive.ply]]` and `[[primitive.stl]]`.
+//! [[primitive.ply]]
+//! path = "bunny.ply"
 //! material_id = 1
 //!
 //! ## Provide an array of materials.
 //! [[material.lambertian]]
 //! id = 1
 //! albedo = [0.8, 0.8, 0.0]
+//!
+//! ## Metals optionally blur their reflections with `fuzz` from 0 to 1.
+//! [[material.metal]]
+//! id = 3
+//! albedo = [0.8, 0.6, 0.2]
+//! fuzz = 0.3
+//!
+//! ## Lights emit `color` scaled by `strength`, optionally from the front only.
+//! [[material.light]]
+//! id = 4
+//! color = [1.0, 0.9, 0.8]
+//! strength = 4.0
+//! one_sided = true
+//!
+//! ## Dielectrics refract with an index of refraction, using "schlick" or
+//! ## "exact" Fresnel, and optionally absorb light per unit distance inside.
+//! [[material.dielectric]]
+//! id = 2
+//! ior = 1.5
+//! fresnel = "exact"
+//! absorption = [0.0, 0.1, 0.2]
 //! ```
-//! ```rust
-//! use rayt_rs::scene_builder::SceneBuilder;
+//! ```rust,no_run
+//! use rayt_rs::{image::ppm, scene_builder::SceneBuilder};
+//! use std::io;
 //!
 //! fn main() -> anyhow::Result<()> {
 //!     // Safety: just for logging
@@ -39,28 +149,61 @@
 //!
 //!     let (camera, world) = SceneBuilder::build("scene1.toml")?;
 //!
-//!     camera.render(world);
+//!     let image = camera.render(&world);
+//!     ppm::write_p3(&image, io::stdout().lock())?;
 //!
 //!     Ok(())
 //! }
 //! ```
 
-use crate::{camera::*, materials::*, math::primitives::*, world::World};
+use crate::{
+    background::Background,
+    bvh::BvhConfig,
+    camera::*,
+    environment::EnvironmentMap,
+    loaders::{obj::ObjModel, ply, stl},
+    materials::*,
+    math::{
+        primitives::*,
+        transform::{Transform, Transformed},
+    },
+    tonemap::DisplayTransform,
+    world::World,
+};
 use serde::Deserialize;
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 pub struct SceneBuilder;
 
 impl SceneBuilder {
     pub fn build(path: impl AsRef<Path>) -> Result<(Camera, World), SceneError> {
+        let path = path.as_ref();
         let toml_str =
             fs::read_to_string(path).map_err(|err| SceneError::FileReadError(err.to_string()))?;
 
         let config: Config = toml::from_str(&toml_str)
             .map_err(|err| SceneError::ConfigDeError(err.message().to_string()))?;
 
-        let camera = config.camera.build();
+        let camera = config.camera.display(config.output).build();
         let mut world = World::default();
+        world.set_bvh_config(config.bvh);
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        world.set_background(match config.background {
+            BackgroundConfig::Environment(file) => EnvironmentMap::open(dir.join(&file.path))
+                .map(|map| {
+                    let map = map.rotation(file.rotation).intensity(file.intensity);
+                    Background::environment(map)
+                })
+                .map_err(|err| {
+                    SceneError::ImageLoadError(format!("{}: {err}", file.path.display()))
+                })?,
+            BackgroundConfig::Builtin(background) => background,
+        });
 
         let mut real_ids: HashMap<MaterialId, MaterialId> = HashMap::default();
 
@@ -79,23 +222,77 @@ impl SceneBuilder {
             ($x:expr) => {
                 if let Some(ps) = $x {
                     for mut p in ps {
-                        if let Some(&real_id) = real_ids.get(&p.material_id) {
-                            p.material_id = real_id;
-                            world.add_object(p);
+                        if let Some(&real_id) = real_ids.get(&p.data.material_id) {
+                            p.data.material_id = real_id;
+                            match p.transform {
+                                Some(transform) => {
+                                    world.add_object(Transformed::new(p.data, transform))
+                                }
+                                None => world.add_object(p.data),
+                            }
                         } else {
-                            panic!("no material: {:?}", p.material_id);
+                            panic!("no material: {:?}", p.data.material_id);
                         }
                     }
                 }
             };
         }
 
+        material!(config.material.dielectric);
         material!(config.material.diffuse);
         material!(config.material.lambertian);
+        material!(config.material.light);
         material!(config.material.metal);
         material!(config.material.normals);
 
         primitive!(config.primitive.sphere);
+        primitive!(config.primitive.triangle);
+        primitive!(config.primitive.mesh);
+        primitive!(config.primitive.quad);
+        primitive!(config.primitive.disk);
+        primitive!(config.primitive.plane);
+        primitive!(config.primitive.cuboid);
+        primitive!(config.primitive.cylinder);
+        primitive!(config.primitive.cone);
+        primitive!(config.primitive.capsule);
+        primitive!(config.primitive.torus);
+
+        for obj in config.primitive.obj.into_iter().flatten() {
+            let Some(&real_id) = real_ids.get(&obj.material_id) else {
+                panic!("no material: {:?}", obj.material_id);
+            };
+
+            ObjModel::load(dir.join(&obj.path))
+                .and_then(|model| model.add_to(&mut world, real_id, &obj.transform))
+                .map_err(|err| {
+                    SceneError::MeshLoadError(format!("{}: {err}", obj.path.display()))
+                })?;
+        }
+
+        macro_rules! mesh_file {
+            ($x:expr, $load:path) => {
+                for file in $x.into_iter().flatten() {
+                    let Some(&real_id) = real_ids.get(&file.material_id) else {
+                        panic!("no material: {:?}", file.material_id);
+                    };
+
+                    let mesh = $load(dir.join(&file.path))
+                        .and_then(|mut data| {
+                            if !file.transform.is_identity() {
+                                data.transform(file.transform.to_affine());
+                            }
+                            Ok(TriangleMesh::new(data, real_id)?)
+                        })
+                        .map_err(|err| {
+                            SceneError::MeshLoadError(format!("{}: {err}", file.path.display()))
+                        })?;
+                    world.add_object(mesh);
+                }
+            };
+        }
+
+        mesh_file!(config.primitive.ply, ply::load);
+        mesh_file!(config.primitive.stl, stl::load);
 
         Ok((camera, world))
     }
@@ -104,19 +301,99 @@ impl SceneBuilder {
 #[derive(Deserialize)]
 struct Config {
     camera: CameraBuilder,
+    #[serde(default)]
+    output: DisplayTransform,
+    #[serde(default)]
+    bvh: BvhConfig,
+    #[serde(default)]
+    background: BackgroundConfig,
     primitive: PrimitiveConfig,
     material: MaterialConfig,
 }
 
+/// The `[background]` table, which may name an image to load.
+enum BackgroundConfig {
+    Environment(EnvironmentFile),
+    Builtin(Background),
+}
+
+impl<'de> Deserialize<'de> for BackgroundConfig {
+    /// Reads `type` first, so errors in the rest of the table name the
+    /// field at fault.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let table = toml::Value::deserialize(deserializer)?;
+        match table.get("type").and_then(toml::Value::as_str) {
+            Some("environment") => EnvironmentFile::deserialize(table)
+                .map(BackgroundConfig::Environment)
+                .map_err(D::Error::custom),
+            _ => Background::deserialize(table)
+                .map(BackgroundConfig::Builtin)
+                .map_err(D::Error::custom),
+        }
+    }
+}
+
+impl Default for BackgroundConfig {
+    fn default() -> Self {
+        BackgroundConfig::Builtin(Background::default())
+    }
+}
+
+/// An environment map loaded from a file.
+#[derive(Deserialize)]
+struct EnvironmentFile {
+    /// The path relative to the scene file.
+    path: PathBuf,
+    /// Degrees about the y axis.
+    #[serde(default)]
+    rotation: f64,
+    #[serde(default = "EnvironmentFile::default_intensity")]
+    intensity: f64,
+}
+
+impl EnvironmentFile {
+    fn default_intensity() -> f64 {
+        1.0
+    }
+}
+
 #[derive(Deserialize)]
 struct PrimitiveConfig {
-    sphere: Option<Vec<Sphere>>,
+    sphere: Option<Vec<TomlPrimitive<Sphere>>>,
+    triangle: Option<Vec<TomlPrimitive<Triangle>>>,
+    mesh: Option<Vec<TomlPrimitive<TriangleMesh>>>,
+    quad: Option<Vec<TomlPrimitive<Quad>>>,
+    disk: Option<Vec<TomlPrimitive<Disk>>>,
+    plane: Option<Vec<TomlPrimitive<Plane>>>,
+    #[serde(rename = "box")]
+    cuboid: Option<Vec<TomlPrimitive<Cuboid>>>,
+    cylinder: Option<Vec<TomlPrimitive<Cylinder>>>,
+    cone: Option<Vec<TomlPrimitive<Cone>>>,
+    capsule: Option<Vec<TomlPrimitive<Capsule>>>,
+    torus: Option<Vec<TomlPrimitive<Torus>>>,
+    obj: Option<Vec<MeshFile>>,
+    ply: Option<Vec<MeshFile>>,
+    stl: Option<Vec<MeshFile>>,
+}
+
+/// A mesh loaded from a file.
+#[derive(Deserialize)]
+struct MeshFile {
+    /// The path relative to the scene file.
+    path: PathBuf,
+    #[serde(default)]
+    transform: Transform,
+    material_id: MaterialId,
 }
 
 #[derive(Deserialize)]
 struct MaterialConfig {
+    dielectric: Option<Vec<TomlMaterial<Dielectric>>>,
     diffuse: Option<Vec<TomlMaterial<Diffuse>>>,
     lambertian: Option<Vec<TomlMaterial<Lambertian>>>,
+    light: Option<Vec<TomlMaterial<DiffuseLight>>>,
     metal: Option<Vec<TomlMaterial<Metal>>>,
     normals: Option<Vec<TomlMaterial<Normals>>>,
 }
@@ -125,6 +402,8 @@ struct MaterialConfig {
 pub enum SceneError {
     FileReadError(String),
     ConfigDeError(String),
+    MeshLoadError(String),
+    ImageLoadError(String),
 }
 
 impl std::fmt::Display for SceneError {
@@ -132,8 +411,52 @@ impl std::fmt::Display for SceneError {
         match self {
             SceneError::FileReadError(s) => write!(f, "Config file read error: {s}"),
             SceneError::ConfigDeError(s) => write!(f, "Config deserialization error: {s}"),
+            SceneError::MeshLoadError(s) => write!(f, "Mesh load error: {s}"),
+            SceneError::ImageLoadError(s) => write!(f, "Image load error: {s}"),
         }
     }
 }
 
 impl std::error::Error for SceneError {}
+
+mod tests {
+    #[test]
+    fn deserialize_background() {
+        use super::*;
+        use crate::color::Color;
+
+        #[derive(Deserialize)]
+        struct Config {
+            background: BackgroundConfig,
+        }
+
+        let toml_str = r#"
+            [background]
+            type = "environment"
+            path = "sky.hdr"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let BackgroundConfig::Environment(file) = config.background else {
+            panic!("expected an environment map");
+        };
+        assert!(Path::new("sky.hdr") == file.path);
+        assert!(0.0 == file.rotation && 1.0 == file.intensity);
+
+        let toml_str = "[background]\ntype = \"constant\"\ncolor = [0.5, 0.5, 0.5]";
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let expected = Background::constant(Color::new(0.5, 0.5, 0.5));
+        assert!(matches!(config.background, BackgroundConfig::Builtin(b) if b == expected));
+
+        // Errors name the value at fault.
+        let err = toml::from_str::<Config>("[background]\ntype = \"bogus\"")
+            .err()
+            .unwrap();
+        assert!(err.message().contains("unknown variant `bogus`"));
+        assert!(err.message().contains("`environment`"));
+
+        let err = toml::from_str::<Config>("[background]\ntype = \"environment\"")
+            .err()
+            .unwrap();
+        assert!(err.message().contains("missing field `path`"));
+    }
+}
@@ -0,0 +1,382 @@
+//! A physically based daylight sky.
+
+use crate::color::Color;
+use crate::math::{
+    constants::PI,
+    types::{UnitVec3, Vec3},
+};
+use rand::Rng;
+
+/// Extraterrestrial illuminance of the sun in lux.
+const SOLAR_ILLUMINANCE: f64 = 128_000.0;
+
+/// Daylight from the analytic sky model of Preetham, Shirley and Smits,
+/// with a sun disk seen through the same atmosphere.
+///
+/// The sun stands `elevation` degrees above the horizon, turned `azimuth`
+/// degrees from -Z toward +X. Turbidity measures haze, from about 2 for a
+/// clear sky to 10 for a hazy one.
+///
+/// Radiance is scaled so the sun above the atmosphere gives an irradiance of
+/// pi, under which a white diffuse surface facing it has a radiance of one.
+#[derive(Clone, PartialEq, serde::Deserialize)]
+#[serde(from = "TomlSky")]
+pub struct PhysicalSky {
+    /// Degrees above the horizon.
+    elevation: f64,
+    /// Degrees from -Z toward +X.
+    azimuth: f64,
+    turbidity: f64,
+    /// Angular diameter of the sun in degrees.
+    sun_size: f64,
+    /// Scales the radiance of the sky and sun.
+    intensity: f64,
+    /// Points toward the center of the sun.
+    sun_direction: Vec3,
+    /// Cosine of the angular radius of the sun.
+    cos_sun_radius: f64,
+    sun_radiance: Color,
+    /// Luminance in cd/m^2 and chromaticity at the zenith.
+    zenith: Vec3,
+    /// Perez distribution coefficients for luminance and each chromaticity.
+    perez: [[f64; 5]; 3],
+}
+
+impl PhysicalSky {
+    /// Constructs a clear sky with the sun at `elevation` degrees above the
+    /// horizon and `azimuth` degrees from -Z toward +X.
+    ///
+    /// The model holds for daylight, so the elevation is clamped to `[0, 90]`.
+    pub fn new(elevation: f64, azimuth: f64) -> Self {
+        PhysicalSky {
+            elevation,
+            azimuth,
+            turbidity: 3.0,
+            sun_size: 0.53,
+            intensity: 1.0,
+            sun_direction: Vec3::Y,
+            cos_sun_radius: 1.0,
+            sun_radiance: Color::BLACK,
+            zenith: Vec3::ZERO,
+            perez: [[0.0; 5]; 3],
+        }
+        .update()
+    }
+
+    /// Sets the haziness of the air, clamped to `[1.7, 10]`.
+    pub fn turbidity(mut self, turbidity: f64) -> Self {
+        self.turbidity = turbidity;
+        self.update()
+    }
+
+    /// Sets the angular diameter of the sun in degrees, or zero to hide it.
+    ///
+    /// A larger sun softens shadows without changing the light it gives.
+    pub fn sun_size(mut self, degrees: f64) -> Self {
+        self.sun_size = degrees;
+        self.update()
+    }
+
+    /// Scales the radiance of the sky and sun.
+    pub fn intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self.update()
+    }
+
+    /// Returns the direction toward the center of the sun.
+    pub fn sun_direction(&self) -> UnitVec3 {
+        UnitVec3::new_unchecked(self.sun_direction)
+    }
+
+    /// Returns the radiance of the sky, and of the sun inside its disk.
+    pub fn radiance(&self, direction: UnitVec3) -> Color {
+        let sky = self.sky(direction);
+        if self.in_sun(direction) {
+            sky + self.sun_radiance
+        } else {
+            sky
+        }
+    }
+
+    /// Samples a direction uniformly within the sun disk, returning it with
+    /// its probability density over solid angle.
+    ///
+    /// Returns `None` if the sun is hidden.
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<(UnitVec3, f64)> {
+        if self.sun_size <= 0.0 {
+            return None;
+        }
+
+        let cos_theta = 1.0 - rng.random::<f64>() * (1.0 - self.cos_sun_radius);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * rng.random::<f64>();
+        let (t, b) = self.sun_direction.any_orthonormal_pair();
+
+        let direction =
+            sin_theta * (phi.cos() * t + phi.sin() * b) + cos_theta * self.sun_direction;
+        Some((UnitVec3::new_normalize(direction), self.sun_pdf()))
+    }
+
+    /// Returns the probability density over solid angle with which
+    /// [`sample`](PhysicalSky::sample) picks `direction`.
+    pub fn pdf(&self, direction: UnitVec3) -> f64 {
+        if self.in_sun(direction) {
+            self.sun_pdf()
+        } else {
+            0.0
+        }
+    }
+
+    fn in_sun(&self, direction: UnitVec3) -> bool {
+        self.sun_size > 0.0 && direction.dot(self.sun_direction) >= self.cos_sun_radius
+    }
+
+    fn sun_pdf(&self) -> f64 {
+        1.0 / (2.0 * PI * (1.0 - self.cos_sun_radius))
+    }
+
+    /// Returns the radiance of the sky without the sun. Directions below the
+    /// horizon see the horizon.
+    fn sky(&self, direction: UnitVec3) -> Color {
+        let cos_theta = direction.y.max(1e-3);
+        let cos_gamma = direction.dot(self.sun_direction).clamp(-1.0, 1.0);
+        let gamma = cos_gamma.acos();
+
+        let theta_sun = self.theta_sun();
+        let [luminance, x, y] = [0, 1, 2].map(|i| {
+            let c = &self.perez[i];
+            self.zenith[i] * perez(c, cos_theta, gamma, cos_gamma)
+                / perez(c, 1.0, theta_sun, theta_sun.cos())
+        });
+
+        Color(xyy_to_rgb(x, y, luminance) * self.radiance_scale())
+    }
+
+    /// Returns the angle of the sun from the zenith in radians.
+    fn theta_sun(&self) -> f64 {
+        (90.0 - self.elevation.clamp(0.0, 90.0)).to_radians()
+    }
+
+    /// Converts cd/m^2 to the units of the renderer.
+    fn radiance_scale(&self) -> f64 {
+        PI / SOLAR_ILLUMINANCE * self.intensity
+    }
+
+    /// Recomputes the model after a parameter changes.
+    fn update(mut self) -> Self {
+        let t = self.turbidity.clamp(1.7, 10.0);
+        let theta = self.theta_sun();
+        let azimuth = self.azimuth.to_radians();
+
+        self.sun_direction = Vec3::new(
+            theta.sin() * azimuth.sin(),
+            theta.cos(),
+            -theta.sin() * azimuth.cos(),
+        );
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta);
+        let luminance = ((4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192) * 1000.0;
+        let chromaticity = |m: [[f64; 4]; 3]| {
+            let powers = [theta.powi(3), theta.powi(2), theta, 1.0];
+            let row = |r: [f64; 4]| r.iter().zip(powers).map(|(a, b)| a * b).sum::<f64>();
+            t * t * row(m[0]) + t * row(m[1]) + row(m[2])
+        };
+        let x = chromaticity([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886],
+        ]);
+        let y = chromaticity([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688],
+        ]);
+        self.zenith = Vec3::new(luminance, x, y);
+
+        self.perez = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        self.cos_sun_radius = (self.sun_size.max(0.0) / 2.0).to_radians().cos();
+        self.sun_radiance = if self.sun_size > 0.0 {
+            // Spread the irradiance of the sun evenly over its disk.
+            let solid_angle = 2.0 * PI * (1.0 - self.cos_sun_radius);
+            Color(sun_transmittance(theta, t)) * (PI / solid_angle * self.intensity)
+        } else {
+            Color::BLACK
+        };
+
+        self
+    }
+}
+
+/// The Perez sky luminance distribution.
+fn perez(c: &[f64; 5], cos_theta: f64, gamma: f64, cos_gamma: f64) -> f64 {
+    let [a, b, c, d, e] = *c;
+    (1.0 + a * (b / cos_theta).exp()) * (1.0 + c * (d * gamma).exp() + e * cos_gamma * cos_gamma)
+}
+
+/// Returns the fraction of red, green and blue sunlight passing through the
+/// atmosphere by Rayleigh and aerosol scattering.
+fn sun_transmittance(theta: f64, turbidity: f64) -> Vec3 {
+    // Relative optical mass of the air along the path to the sun.
+    let mass = 1.0 / (theta.cos() + 0.15 * (93.885 - theta.to_degrees()).powf(-1.253));
+    let beta = 0.04608 * turbidity - 0.04586;
+
+    // Wavelengths in micrometers.
+    Vec3::new(0.680, 0.550, 0.440).map(|lambda| {
+        let rayleigh = (-0.008735 * lambda.powf(-4.08) * mass).exp();
+        let aerosol = (-beta * lambda.powf(-1.3) * mass).exp();
+        rayleigh * aerosol
+    })
+}
+
+/// Converts CIE xyY to linear Rec. 709 RGB.
+fn xyy_to_rgb(x: f64, y: f64, luminance: f64) -> Vec3 {
+    let xyz = Vec3::new(x / y * luminance, luminance, (1.0 - x - y) / y * luminance);
+    Vec3::new(
+        Vec3::new(3.2406, -1.5372, -0.4986).dot(xyz),
+        Vec3::new(-0.9689, 1.8758, 0.0415).dot(xyz),
+        Vec3::new(0.0557, -0.2040, 1.0570).dot(xyz),
+    )
+    .max(Vec3::ZERO)
+}
+
+/// `[background]` table with `type = "physical"`.
+#[derive(serde::Deserialize)]
+#[serde(default)]
+struct TomlSky {
+    /// Degrees above the horizon.
+    elevation: f64,
+    /// Degrees from -Z toward +X.
+    azimuth: f64,
+    turbidity: f64,
+    /// Angular diameter of the sun in degrees.
+    sun_size: f64,
+    intensity: f64,
+}
+
+impl Default for TomlSky {
+    fn default() -> Self {
+        TomlSky {
+            elevation: 45.0,
+            azimuth: 0.0,
+            turbidity: 3.0,
+            sun_size: 0.53,
+            intensity: 1.0,
+        }
+    }
+}
+
+impl From<TomlSky> for PhysicalSky {
+    fn from(value: TomlSky) -> Self {
+        PhysicalSky::new(value.elevation, value.azimuth)
+            .turbidity(value.turbidity)
+            .sun_size(value.sun_size)
+            .intensity(value.intensity)
+    }
+}
+
+mod tests {
+    #[test]
+    fn radiance() {
+        use super::*;
+
+        let sky = PhysicalSky::new(30.0, 90.0);
+        assert!(
+            sky.sun_direction()
+                .abs_diff_eq(Vec3::new(3f64.sqrt() / 2.0, 0.5, 0.0), 1e-12)
+        );
+
+        // Blue overhead, brighter toward the sun than away from it.
+        let zenith = sky.radiance(Vec3::Y.into());
+        assert!(zenith.z > zenith.x);
+        let toward = sky.radiance(Vec3::new(1.0, 0.3, 0.0).into());
+        let away = sky.radiance(Vec3::new(-1.0, 0.3, 0.0).into());
+        assert!(toward.luminance() > away.luminance());
+
+        // The sun outshines the sky and reddens as it sets.
+        let sun = sky.radiance(sky.sun_direction());
+        assert!(sun.luminance() > 1000.0 * toward.luminance());
+        let low = PhysicalSky::new(5.0, 0.0);
+        let low_sun = low.radiance(low.sun_direction());
+        assert!(low_sun.x / low_sun.z > sun.x / sun.z);
+
+        // Haze dims the sun.
+        let hazy = sky.clone().turbidity(8.0);
+        assert!(hazy.radiance(hazy.sun_direction()).luminance() < sun.luminance());
+
+        let hidden = sky.sun_size(0.0);
+        assert!(hidden.radiance(hidden.sun_direction()) == hidden.sky(hidden.sun_direction()));
+        assert!(hidden.sample(&mut rand::rng()).is_none());
+    }
+
+    #[test]
+    fn sun_sampling() {
+        use super::*;
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let sky = PhysicalSky::new(60.0, 30.0).sun_size(2.0).turbidity(2.0);
+        let mut rng = StdRng::seed_from_u64(3);
+
+        // The sun at the zenith of a facing surface gives an irradiance of
+        // pi times the transmittance, independent of its size.
+        let n = 10_000;
+        let mut irradiance = Vec3::ZERO;
+        for _ in 0..n {
+            let (direction, pdf) = sky.sample(&mut rng).unwrap();
+            assert!(sky.in_sun(direction));
+            assert!(pdf == sky.pdf(direction));
+            let cos = direction.dot(sky.sun_direction);
+            irradiance += *sky.sun_radiance * cos / pdf;
+        }
+        let expected = sun_transmittance(sky.theta_sun(), 2.0) * PI;
+        assert!((irradiance / n as f64).abs_diff_eq(expected, 1e-3));
+
+        assert!(0.0 == sky.pdf(Vec3::NEG_Y.into()));
+    }
+
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use crate::background::Background;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [background]
+            type = "physical"
+            elevation = 20.0
+            azimuth = -45.0
+            turbidity = 5.0
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            background: Background,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let expected = PhysicalSky::new(20.0, -45.0).turbidity(5.0);
+        assert!(Background::physical(expected) == config.background);
+    }
+}
@@ -0,0 +1,239 @@
+//! Display transforms from linear radiance to display values.
+
+use crate::{color::Color, math::types::Interval};
+
+/// Tone mapping operators that compress radiance into `[0, 1]`.
+#[derive(Copy, Clone, Default, PartialEq, Debug, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToneMap {
+    /// Clip values above one.
+    #[default]
+    Clamp,
+    /// `x / (1 + x)`.
+    Reinhard,
+    /// Reinhard that maps the white point to one.
+    ExtendedReinhard,
+    /// Narkowicz's fit of the ACES filmic curve.
+    Aces,
+    /// John Hable's Uncharted 2 filmic curve.
+    Hable,
+}
+
+impl ToneMap {
+    /// Maps one channel of linear radiance.
+    pub fn apply(self, x: f64, white_point: f64) -> f64 {
+        match self {
+            ToneMap::Clamp => x,
+            ToneMap::Reinhard => x / (1.0 + x),
+            ToneMap::ExtendedReinhard => x * (1.0 + x / white_point.powi(2)) / (1.0 + x),
+            ToneMap::Aces => {
+                let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+                (x * (a * x + b)) / (x * (c * x + d) + e)
+            }
+            ToneMap::Hable => {
+                const EXPOSURE_BIAS: f64 = 2.0;
+                const WHITE: f64 = 11.2;
+                hable(EXPOSURE_BIAS * x) / hable(WHITE)
+            }
+        }
+    }
+}
+
+/// Hable's partial filmic curve.
+fn hable(x: f64) -> f64 {
+    let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+    ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f
+}
+
+/// Opto-electronic transfer functions that encode display values.
+#[derive(Copy, Clone, Default, PartialEq, Debug, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferFunction {
+    /// A pure power curve, `x^(1 / gamma)`.
+    #[default]
+    Gamma,
+    /// The piecewise sRGB curve.
+    Srgb,
+}
+
+/// Converts linear colors to display values.
+///
+/// Applies exposure, then the tone map, then the transfer function.
+#[derive(Copy, Clone, PartialEq, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct DisplayTransform {
+    /// Exposure in stops.
+    exposure: f64,
+    tone_map: ToneMap,
+    /// Smallest radiance mapped to white by [`ToneMap::ExtendedReinhard`].
+    white_point: f64,
+    transfer: TransferFunction,
+    /// Exponent for [`TransferFunction::Gamma`].
+    gamma: f64,
+}
+
+impl DisplayTransform {
+    const INTENSITY: Interval = Interval(0.0, 0.999);
+
+    pub fn new() -> Self {
+        DisplayTransform {
+            exposure: 0.0,
+            tone_map: ToneMap::Clamp,
+            white_point: 1.0,
+            transfer: TransferFunction::Gamma,
+            gamma: 2.2,
+        }
+    }
+
+    /// Sets the exposure in stops.
+    pub fn exposure(mut self, ev: impl Into<f64>) -> Self {
+        self.exposure = ev.into();
+        self
+    }
+
+    pub fn tone_map(mut self, tone_map: ToneMap) -> Self {
+        self.tone_map = tone_map;
+        self
+    }
+
+    /// Sets the smallest radiance mapped to white by [`ToneMap::ExtendedReinhard`].
+    pub fn white_point(mut self, white: impl Into<f64>) -> Self {
+        self.white_point = white.into();
+        self
+    }
+
+    pub fn transfer(mut self, transfer: TransferFunction) -> Self {
+        self.transfer = transfer;
+        self
+    }
+
+    /// Sets the exponent for [`TransferFunction::Gamma`].
+    pub fn gamma(mut self, gamma: impl Into<f64>) -> Self {
+        self.gamma = gamma.into();
+        self
+    }
+
+    /// Returns the display encoded color in `[0, 1]`.
+    pub fn apply(&self, color: Color) -> Color {
+        let scale = 2f64.powf(self.exposure);
+        color
+            .map(|e| {
+                let mapped = self.tone_map.apply(e * scale, self.white_point);
+                self.encode(mapped.clamp(0.0, 1.0))
+            })
+            .into()
+    }
+
+    /// Applies the transfer function to one channel.
+    fn encode(&self, x: f64) -> f64 {
+        match self.transfer {
+            TransferFunction::Gamma => x.powf(1.0 / self.gamma),
+            TransferFunction::Srgb if x <= 0.0031308 => 12.92 * x,
+            TransferFunction::Srgb => 1.055 * x.powf(1.0 / 2.4) - 0.055,
+        }
+    }
+
+    /// Returns the display color quantized to 8 bits per channel.
+    pub fn to_rgb8(&self, color: Color) -> [u8; 3] {
+        self.apply(color)
+            .to_array()
+            .map(|e| (256.0 * DisplayTransform::INTENSITY.clamp(e)) as u8)
+    }
+
+    /// Returns the display color quantized to 16 bits per channel.
+    pub fn to_rgb16(&self, color: Color) -> [u16; 3] {
+        self.apply(color)
+            .to_array()
+            .map(|e| (65535.0 * e).round() as u16)
+    }
+}
+
+impl Default for DisplayTransform {
+    fn default() -> Self {
+        DisplayTransform::new()
+    }
+}
+
+mod tests {
+    #[test]
+    fn deserialize() {
+        use super::*;
+        use serde::Deserialize;
+
+        let toml_str = r#"
+            [output]
+            exposure = 1.5
+            tone_map = "extended_reinhard"
+            white_point = 4.0
+            transfer = "srgb"
+        "#;
+
+        #[derive(Deserialize)]
+        struct Config {
+            output: DisplayTransform,
+        }
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let expected = DisplayTransform::new()
+            .exposure(1.5)
+            .tone_map(ToneMap::ExtendedReinhard)
+            .white_point(4.0)
+            .transfer(TransferFunction::Srgb);
+        assert!(expected == config.output);
+    }
+
+    #[test]
+    fn default_is_gamma_and_clip() {
+        use super::*;
+
+        let display = DisplayTransform::default();
+        assert!([0, 0, 0] == display.to_rgb8(Color::BLACK));
+        assert!([186, 255, 255] == display.to_rgb8(Color::new(0.5, 1.0, 8.0)));
+    }
+
+    #[test]
+    fn tone_maps_are_bounded_and_monotonic() {
+        use super::*;
+
+        for tone_map in [
+            ToneMap::Reinhard,
+            ToneMap::ExtendedReinhard,
+            ToneMap::Aces,
+            ToneMap::Hable,
+        ] {
+            assert!(tone_map.apply(0.0, 4.0).abs() < 1e-12);
+
+            let mut prev = 0.0;
+            for i in 1..100 {
+                let y = tone_map.apply(i as f64 * 0.04, 4.0);
+                assert!(y > prev);
+                prev = y;
+            }
+
+            let display = DisplayTransform::new().tone_map(tone_map);
+            assert!([255, 255, 255] == display.to_rgb8(Color::new(1e6, 1e6, 1e6)));
+        }
+
+        assert!((ToneMap::ExtendedReinhard.apply(4.0, 4.0) - 1.0).abs() < 1e-12);
+        assert!((ToneMap::Hable.apply(11.2 / 2.0, 1.0) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn exposure_and_srgb() {
+        use super::*;
+
+        let display = DisplayTransform::new().transfer(TransferFunction::Srgb);
+        let c = display.apply(Color::new(0.001, 0.5, 1.0));
+        assert!((c.x - 0.01292).abs() < 1e-12);
+        assert!((c.y - 0.735357).abs() < 1e-6);
+        assert!((c.z - 1.0).abs() < 1e-12);
+
+        let display = display.exposure(1.0);
+        assert!(
+            display.apply(Color::new(0.25, 0.0, 0.0))
+                == DisplayTransform::new()
+                    .transfer(TransferFunction::Srgb)
+                    .apply(Color::new(0.5, 0.0, 0.0))
+        );
+    }
+}
@@ -1,9 +1,12 @@
 //! This module defines the world, which stores all scene objects and materials.
 
 use crate::{
+    background::Background,
+    bvh::{Bvh, BvhConfig},
     hittable::{Hittable, HittableList},
     materials::{Material, MaterialId, MaterialMap},
 };
+use std::sync::OnceLock;
 
 /// The world contains all materials and objects.
 #[derive(Default)]
@@ -12,6 +15,12 @@ pub struct World {
     objects: HittableList,
     /// The map of materials in the world.
     materials: MaterialMap,
+    /// Settings for building `bvh`.
+    bvh_config: BvhConfig,
+    /// The hierarchy over `objects`, built on first use.
+    bvh: OnceLock<Bvh>,
+    /// What rays see when they miss every object.
+    background: Background,
 }
 
 impl World {
@@ -21,6 +30,7 @@ impl World {
         T: Hittable + Send + Sync + 'static,
     {
         self.objects.add(o);
+        self.bvh.take();
     }
 
     /// Adds a material to the world.
@@ -31,13 +41,38 @@ impl World {
         self.materials.insert(material)
     }
 
-    /// Returns an immutable reference to hittable objects.
-    pub(crate) fn objects(&self) -> &HittableList {
-        &self.objects
+    /// Sets how the object hierarchy is built.
+    pub fn set_bvh_config(&mut self, config: BvhConfig) {
+        self.bvh_config = config;
+        self.bvh.take();
+    }
+
+    /// Sets what rays see when they miss every object.
+    pub fn set_background(&mut self, background: Background) {
+        self.background = background;
+    }
+
+    /// Returns the accelerated hierarchy over all objects, building it if needed.
+    pub(crate) fn hittable(&self) -> &dyn Hittable {
+        self.bvh.get_or_init(|| {
+            log::info!("Building BVH");
+            let start = std::time::Instant::now();
+            let bvh = Bvh::new(self.objects.objects(), self.bvh_config);
+            log::info!(
+                "Built BVH with {} nodes in {:.2?}",
+                bvh.len(),
+                start.elapsed()
+            );
+            bvh
+        })
     }
 
     /// Returns an immutable reference to materials.
     pub(crate) fn materials(&self) -> &MaterialMap {
         &self.materials
     }
+
+    pub(crate) fn background(&self) -> &Background {
+        &self.background
+    }
 }